@@ -0,0 +1,172 @@
+//! Benchmarks of the shared building blocks on synthetic inputs of growing size
+//!
+//! Run with `cargo bench`, or `cargo bench -- <name>` for a single group. Criterion keeps the
+//! previous results in `target/criterion` and reports changes against them.
+
+use aoc2023::day15::hash;
+use aoc2023::day22::{perform_fall, Brick, StopAfterFirstFall};
+use aoc2023::day23::dijkstra;
+use aoc2023::day25::minimum_cut;
+use aoc2023::util::{build_grid, Direction, Position};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use petgraph::graph::UnGraph;
+
+/// Square grid of `size` lines in a pattern of walls and open tiles
+fn grid_lines(size: usize) -> Vec<String> {
+    (0..size)
+        .map(|y| {
+            (0..size)
+                .map(|x| if (x * 7 + y * 3) % 5 == 0 { '#' } else { '.' })
+                .collect()
+        })
+        .collect()
+}
+
+fn bench_build_grid(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build_grid");
+    for size in [16, 64, 256, 1024] {
+        let lines = grid_lines(size);
+        group.throughput(Throughput::Elements((size * size) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &lines, |b, lines| {
+            b.iter(|| build_grid::<bool>(lines).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_advance_in_grid(c: &mut Criterion) {
+    let mut group = c.benchmark_group("advance_in_grid");
+    for size in [16, 64, 256, 1024] {
+        let grid = build_grid::<bool>(&grid_lines(size)).unwrap();
+        group.throughput(Throughput::Elements((size * size * 4) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &grid, |b, grid| {
+            // Every step from every tile, including those leaving the grid at the edges
+            b.iter(|| {
+                let mut inside = 0;
+                for y in 0..size {
+                    for x in 0..size {
+                        let pos = Position::from_grid_pos((y, x));
+                        for dir in Direction::all() {
+                            inside += pos.advance_in_grid(dir, grid).is_some() as usize;
+                        }
+                    }
+                }
+                inside
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_dijkstra(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dijkstra");
+    group.sample_size(10);
+    for size in [16, 32, 64, 128] {
+        let grid = build_grid::<bool>(&grid_lines(size)).unwrap();
+        group.throughput(Throughput::Elements((size * size) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &grid, |b, grid| {
+            // Search the whole grid from a corner, walls cost more to cross than open tiles.
+            // Only moving east and south, as the search keeps the more expensive path to a node
+            // and would go back and forth forever otherwise.
+            let successors = |pos: &Position| {
+                [Direction::E, Direction::S]
+                    .into_iter()
+                    .filter_map(|dir| pos.advance_in_grid(dir, grid))
+                    .map(|next| (next, if grid[next.as_grid_pos()] { 10 } else { 1 }))
+                    .collect::<Vec<_>>()
+            };
+            b.iter(|| dijkstra(&Position { x: 0, y: 0 }, successors, |_| false))
+        });
+    }
+    group.finish();
+}
+
+/// Two cliques of `size` nodes each, connected by three edges like the components of day 25
+fn two_cliques(size: usize) -> UnGraph<String, u32> {
+    let mut graph = UnGraph::new_undirected();
+    let nodes: Vec<_> = (0..2 * size)
+        .map(|i| graph.add_node(format!("n{}", i)))
+        .collect();
+    for clique in nodes.chunks(size) {
+        for (i, &a) in clique.iter().enumerate() {
+            for &b in &clique[i + 1..] {
+                graph.add_edge(a, b, 1);
+            }
+        }
+    }
+    for i in 0..3 {
+        graph.add_edge(nodes[i], nodes[size + i], 1);
+    }
+    graph
+}
+
+fn bench_minimum_cut(c: &mut Criterion) {
+    let mut group = c.benchmark_group("minimum_cut");
+    group.sample_size(10);
+    for size in [8, 16, 32, 64] {
+        let graph = two_cliques(size);
+        group.throughput(Throughput::Elements((2 * size) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &graph, |b, graph| {
+            b.iter_batched(
+                || graph.clone(),
+                |mut graph| minimum_cut(&mut graph),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// `count` bricks floating in a staircase above a 4x4 area, so that every one of them falls
+fn floating_bricks(count: usize) -> Vec<Brick> {
+    (0..count as i64)
+        .map(|i| {
+            let (x, y, z) = (i % 4, (i / 4) % 4, 2 + 3 * i);
+            match i % 3 {
+                0 => ((x, 0, z), (x, 3, z)),
+                1 => ((0, y, z), (3, y, z)),
+                _ => ((x, y, z), (x, y, z + 1)),
+            }
+        })
+        .collect()
+}
+
+fn bench_perform_fall(c: &mut Criterion) {
+    let mut group = c.benchmark_group("perform_fall");
+    group.sample_size(10);
+    for count in [25, 50, 100, 200] {
+        let bricks = floating_bricks(count);
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &bricks, |b, bricks| {
+            b.iter_batched(
+                || bricks.clone(),
+                |mut bricks| perform_fall(&mut bricks, StopAfterFirstFall::No),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_hash(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hash");
+    for len in [4, 64, 1024, 16384] {
+        let step: String = (0..len).map(|i| (b'a' + (i % 26) as u8) as char).collect();
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(len), &step, |b, step| {
+            b.iter(|| hash(step))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_build_grid,
+    bench_advance_in_grid,
+    bench_dijkstra,
+    bench_minimum_cut,
+    bench_perform_fall,
+    bench_hash
+);
+criterion_main!(benches);
@@ -0,0 +1,21 @@
+//! Arbitrary bytes as the input of the day selected by the first byte
+//!
+//! Run with `cargo +nightly fuzz run parse`. Parsers have to reject broken input with an error,
+//! a panic (including an arithmetic overflow) is a crash.
+
+#![no_main]
+
+use aoc2023::SOLUTIONS;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&day, text)) = data.split_first() else {
+        return;
+    };
+    let (_, solution) = SOLUTIONS[day as usize % SOLUTIONS.len()];
+    let lines: Vec<String> = String::from_utf8_lossy(text)
+        .lines()
+        .map(|l| l.to_string())
+        .collect();
+    let _ = solution.parse_any(&lines);
+});
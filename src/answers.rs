@@ -0,0 +1,134 @@
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use serde::Deserialize;
+
+use crate::input::InputVariant;
+use crate::solution::Answer;
+
+/// Answer as written in the answers file, either a plain number or a string
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum ExpectedAnswer {
+    Int(i64),
+    Str(String),
+}
+
+impl ExpectedAnswer {
+    pub fn matches(&self, answer: &Answer) -> bool {
+        self.to_string() == answer.to_string()
+    }
+}
+
+impl fmt::Display for ExpectedAnswer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpectedAnswer::Int(i) => write!(f, "{}", i),
+            ExpectedAnswer::Str(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PartAnswers {
+    part1: Option<ExpectedAnswer>,
+    part2: Option<ExpectedAnswer>,
+}
+
+/// Marks a part that does not apply to an input instead of an answer
+const SKIP: &str = "skip";
+
+/// Known answers per day, input variant and part
+///
+/// The file has one table per day and input variant. A part that does not apply to an input,
+/// e.g. part 1 of an example that only comes with part 2, is marked with `"skip"`. Any other
+/// part left out has no known answer yet.
+///
+/// ```toml
+/// [day1.example]
+/// part1 = "skip"
+/// part2 = 281
+///
+/// [day1.real]
+/// part1 = 54601
+/// part2 = 54078
+/// ```
+#[derive(Debug, Default)]
+pub struct Answers {
+    entries: BTreeMap<(u8, InputVariant), PartAnswers>,
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Answers, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::parse(&text).map_err(|e| format!("Invalid answers file {}: {}", path.display(), e))
+    }
+
+    fn parse(text: &str) -> Result<Answers, String> {
+        let days: BTreeMap<String, BTreeMap<String, PartAnswers>> =
+            toml::from_str(text).map_err(|e| e.to_string())?;
+
+        let mut entries = BTreeMap::new();
+        for (day_key, variants) in days {
+            let day = day_key
+                .strip_prefix("day")
+                .and_then(|d| d.parse::<u8>().ok())
+                .ok_or_else(|| format!("invalid day '{}', expected e.g. 'day7'", day_key))?;
+            for (variant_key, parts) in variants {
+                let variant = variant_key.parse::<InputVariant>()?;
+                entries.insert((day, variant), parts);
+            }
+        }
+        Ok(Answers { entries })
+    }
+
+    /// Add the tables of `other`, replacing tables of the same day and input
+    pub fn extend(&mut self, other: Answers) {
+        self.entries.extend(other.entries);
+    }
+
+    /// Entry of a part as written in the file, including the skip marker
+    fn entry(&self, day: u8, variant: InputVariant, part: u8) -> Option<&ExpectedAnswer> {
+        let parts = self.entries.get(&(day, variant))?;
+        match part {
+            1 => parts.part1.as_ref(),
+            2 => parts.part2.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Whether the part applies to this input, i.e. it is not marked with `"skip"`
+    pub fn applies(&self, day: u8, variant: InputVariant, part: u8) -> bool {
+        !matches!(self.entry(day, variant, part), Some(ExpectedAnswer::Str(s)) if s == SKIP)
+    }
+
+    pub fn expected(&self, day: u8, variant: InputVariant, part: u8) -> Option<&ExpectedAnswer> {
+        self.entry(day, variant, part)
+            .filter(|_| self.applies(day, variant, part))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skipped_and_missing_parts() {
+        let answers = Answers::parse(
+            "[day1.example]\npart1 = \"skip\"\npart2 = 281\n\n[day1.real]\npart1 = 54601\n",
+        )
+        .unwrap();
+
+        assert!(!answers.applies(1, InputVariant::Example, 1));
+        assert_eq!(answers.expected(1, InputVariant::Example, 1), None);
+        assert_eq!(
+            answers.expected(1, InputVariant::Example, 2),
+            Some(&ExpectedAnswer::Int(281))
+        );
+        // Left out parts and inputs without a table still apply, their answer is just missing
+        assert!(answers.applies(1, InputVariant::Real, 2));
+        assert_eq!(answers.expected(1, InputVariant::Real, 2), None);
+        assert!(answers.applies(2, InputVariant::Real, 1));
+    }
+}
@@ -0,0 +1,384 @@
+use std::{
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error;
+use crate::input::{self, InputSource, InputVariant, Inputs};
+use crate::runner::panic_message;
+use crate::solution::{self, DynSolution, Solutions};
+use crate::util::time;
+
+/// Wall time statistics of one phase over all runs, in milliseconds
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PhaseStats {
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub max_ms: f64,
+}
+
+impl PhaseStats {
+    fn from_durations(mut durations: Vec<Duration>) -> PhaseStats {
+        durations.sort_unstable();
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let mid = durations.len() / 2;
+        let median = if durations.len().is_multiple_of(2) {
+            (ms(durations[mid - 1]) + ms(durations[mid])) / 2.0
+        } else {
+            ms(durations[mid])
+        };
+        PhaseStats {
+            min_ms: ms(durations[0]),
+            median_ms: median,
+            max_ms: ms(*durations.last().unwrap()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayBench {
+    pub day: u8,
+    pub parse: PhaseStats,
+    pub part1: PhaseStats,
+    pub part2: PhaseStats,
+}
+
+impl DayBench {
+    fn phases(&self) -> [(&'static str, &PhaseStats); 3] {
+        [
+            ("parse", &self.parse),
+            ("part1", &self.part1),
+            ("part2", &self.part2),
+        ]
+    }
+}
+
+/// Results of a bench run, as stored in a baseline file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub runs: usize,
+    pub days: Vec<DayBench>,
+}
+
+impl BenchReport {
+    pub fn load(path: &Path) -> Result<BenchReport, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid baseline file {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = serde_json::to_string_pretty(self).unwrap();
+        fs::write(path, text).map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+    }
+
+    fn day(&self, day: u8) -> Option<&DayBench> {
+        self.days.iter().find(|d| d.day == day)
+    }
+}
+
+fn bench_day(
+    solution: &dyn DynSolution,
+    inputs: &Inputs,
+    day: u8,
+    variant: InputVariant,
+    runs: usize,
+) -> error::Result<DayBench> {
+    let source = InputSource::File(variant);
+    let lines = input::read_input(inputs, day, source)?;
+
+    let mut parse_times = Vec::with_capacity(runs);
+    let mut part1_times = Vec::with_capacity(runs);
+    let mut part2_times = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let (input, t) = time(|| solution.parse_any(&lines));
+        let input = input.map_err(|e| e.in_file(&input::source_path(inputs, day, source)))?;
+        parse_times.push(t);
+        part1_times.push(time(|| solution.part1_any(&*input)).1);
+        part2_times.push(time(|| solution.part2_any(&*input)).1);
+    }
+
+    Ok(DayBench {
+        day,
+        parse: PhaseStats::from_durations(parse_times),
+        part1: PhaseStats::from_durations(part1_times),
+        part2: PhaseStats::from_durations(part2_times),
+    })
+}
+
+fn format_ms(ms: f64) -> String {
+    if ms < 1.0 {
+        format!("{:.1}µs", ms * 1000.0)
+    } else if ms < 1000.0 {
+        format!("{:.2}ms", ms)
+    } else {
+        format!("{:.2}s", ms / 1000.0)
+    }
+}
+
+/// Benchmark `days` and print a table, comparing the medians against `baseline` if given
+///
+/// A phase whose median got slower than the baseline by more than `threshold_percent` is
+/// flagged as a regression. Days whose input is invalid or whose solver panics are reported and
+/// skipped. Returns the results and the number of regressions.
+pub fn bench(
+    solutions: &Solutions,
+    inputs: &Inputs,
+    days: &[u8],
+    variant: InputVariant,
+    runs: usize,
+    baseline: Option<&BenchReport>,
+    threshold_percent: f64,
+) -> (BenchReport, usize) {
+    let mut report = BenchReport {
+        runs,
+        days: Vec::new(),
+    };
+    let mut regressions = 0;
+
+    let mut header = format!(
+        "{:>3}  {:<5}  {:>10}  {:>10}  {:>10}",
+        "Day", "Phase", "min", "median", "max"
+    );
+    if baseline.is_some() {
+        header += &format!("  {:>10}", "baseline");
+    }
+    println!("{}", header);
+
+    // Panics are reported per day, don't clutter the table with the default message
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    for &day in days {
+        let solution = match solution::find(solutions, day) {
+            Ok(solution) => solution,
+            Err(e) => {
+                eprintln!("{}, skipped", e);
+                continue;
+            }
+        };
+        let day_bench = panic::catch_unwind(AssertUnwindSafe(|| {
+            bench_day(solution, inputs, day, variant, runs)
+        }));
+        let day_bench = match day_bench {
+            Ok(Ok(day_bench)) => day_bench,
+            Ok(Err(e)) => {
+                eprintln!("Day {}: {}, skipped", day, e);
+                continue;
+            }
+            Err(payload) => {
+                eprintln!(
+                    "Day {}: panicked: {}, skipped",
+                    day,
+                    panic_message(&*payload)
+                );
+                continue;
+            }
+        };
+        let base_day = baseline.and_then(|b| b.day(day));
+
+        for (i, (phase, stats)) in day_bench.phases().into_iter().enumerate() {
+            let mut line = format!(
+                "{:>3}  {:<5}  {:>10}  {:>10}  {:>10}",
+                day,
+                phase,
+                format_ms(stats.min_ms),
+                format_ms(stats.median_ms),
+                format_ms(stats.max_ms)
+            );
+            if let Some(base_day) = base_day {
+                let base = base_day.phases()[i].1;
+                let change = (stats.median_ms / base.median_ms - 1.0) * 100.0;
+                line += &format!("  {:>10} ({:+.0}%)", format_ms(base.median_ms), change);
+                if change > threshold_percent {
+                    regressions += 1;
+                    line += "  <-- REGRESSION";
+                }
+            }
+            println!("{}", line);
+        }
+        report.days.push(day_bench);
+    }
+    panic::set_hook(default_hook);
+
+    if baseline.is_some() {
+        println!(
+            "{} regression(s) above {}% compared to the baseline",
+            regressions, threshold_percent
+        );
+    }
+    (report, regressions)
+}
+
+/// [`bench`] `days`, or all days if none are given, against the baseline file `compare` if
+/// given, and save the results to `save` if given
+///
+/// Returns the number of regressions, or an error if any day was skipped because its input is
+/// invalid or its solver panicked.
+#[allow(clippy::too_many_arguments)]
+pub fn bench_files(
+    solutions: &Solutions,
+    inputs: &Inputs,
+    days: &[u8],
+    variant: InputVariant,
+    runs: usize,
+    compare: Option<&Path>,
+    save: Option<&Path>,
+    threshold_percent: f64,
+) -> Result<usize, String> {
+    let baseline = compare.map(BenchReport::load).transpose()?;
+    for &day in days {
+        solution::find(solutions, day)?;
+    }
+    let all_days: Vec<u8> = solutions.iter().map(|&(day, _)| day).collect();
+    let days = match days.is_empty() {
+        true => &all_days,
+        false => days,
+    };
+    let (report, regressions) = bench(
+        solutions,
+        inputs,
+        days,
+        variant,
+        runs,
+        baseline.as_ref(),
+        threshold_percent,
+    );
+    if let Some(path) = save {
+        report.save(path)?;
+    }
+    match days.len() - report.days.len() {
+        0 => Ok(regressions),
+        skipped => Err(format!("{} day(s) could not be benchmarked", skipped)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::error::Result;
+    use crate::solution::{Answer, Solution};
+
+    struct Quick;
+
+    impl Solution for Quick {
+        type Input = usize;
+
+        fn parse(lines: &[String]) -> Result<Self::Input> {
+            Ok(lines.len())
+        }
+
+        fn part1(input: &Self::Input) -> Answer {
+            (*input).into()
+        }
+
+        fn part2(input: &Self::Input) -> Answer {
+            (*input).into()
+        }
+    }
+
+    struct Panicking;
+
+    impl Solution for Panicking {
+        type Input = ();
+
+        fn parse(_lines: &[String]) -> Result<Self::Input> {
+            Ok(())
+        }
+
+        fn part1(_input: &Self::Input) -> Answer {
+            panic!("part 1 is broken")
+        }
+
+        fn part2(_input: &Self::Input) -> Answer {
+            0.into()
+        }
+    }
+
+    fn inputs(tmp: &TempDir) -> Inputs {
+        for day in 1..=4 {
+            fs::write(tmp.path().join(format!("inp{}_1.txt", day)), "a\nb\n").unwrap();
+        }
+        Inputs::flat(tmp.path(), 2023)
+    }
+
+    #[test]
+    fn skips_panicking_day() {
+        let tmp = TempDir::new().unwrap();
+        // Days after a gap are looked up by their number, not their position
+        let solutions: [(u8, &dyn DynSolution); 2] = [(1, &Panicking), (4, &Quick)];
+
+        let (report, regressions) = bench(
+            &solutions,
+            &inputs(&tmp),
+            &[1, 4],
+            InputVariant::Example,
+            2,
+            None,
+            10.0,
+        );
+
+        assert_eq!(report.days.len(), 1);
+        assert_eq!(report.days[0].day, 4);
+        assert_eq!(regressions, 0);
+
+        // A skipped day makes the whole run fail, even without a baseline
+        let bench_files = |days: &[u8]| {
+            bench_files(
+                &solutions,
+                &inputs(&tmp),
+                days,
+                InputVariant::Example,
+                1,
+                None,
+                None,
+                10.0,
+            )
+        };
+        assert_eq!(bench_files(&[4]), Ok(0));
+        assert_eq!(
+            bench_files(&[]),
+            Err("1 day(s) could not be benchmarked".to_string())
+        );
+        assert_eq!(bench_files(&[2]), Err("Day 2 has no solver".to_string()));
+    }
+
+    #[test]
+    fn counts_regressions() {
+        let tmp = TempDir::new().unwrap();
+        let solutions: [(u8, &dyn DynSolution); 1] = [(1, &Quick)];
+        // Nothing can be faster than no time at all
+        let instant = PhaseStats {
+            min_ms: 0.0,
+            median_ms: 0.0,
+            max_ms: 0.0,
+        };
+        let baseline = BenchReport {
+            runs: 1,
+            days: vec![DayBench {
+                day: 1,
+                parse: instant,
+                part1: instant,
+                part2: instant,
+            }],
+        };
+
+        let (_, regressions) = bench(
+            &solutions,
+            &inputs(&tmp),
+            &[1],
+            InputVariant::Example,
+            2,
+            Some(&baseline),
+            10.0,
+        );
+
+        assert_eq!(regressions, 3);
+    }
+}
@@ -0,0 +1,166 @@
+use regex::Regex;
+
+use crate::error::{OrExpected, Result};
+use crate::solution::{Answer, Solution};
+use crate::util;
+
+fn numeral_to_int(c: &str) -> i64 {
+    match c {
+        "1" | "one" => 1,
+        "2" | "two" => 2,
+        "3" | "three" => 3,
+        "4" | "four" => 4,
+        "5" | "five" => 5,
+        "6" | "six" => 6,
+        "7" | "seven" => 7,
+        "8" | "eight" => 8,
+        "9" | "nine" => 9,
+        _ => unreachable!("the regexes only match digits and spelled-out digits"),
+    }
+}
+
+/// Values of the first and last match of `re` in `line`, if there is any
+fn first_and_last(line: &str, re: &Regex) -> Option<(i64, i64)> {
+    let p1 = re.find(line)?;
+    let mut p2 = p1;
+    // Matches may overlap ("twone"), so search again from every match start
+    while let Some(x) = re.find_at(line, p2.start() + 1) {
+        p2 = x;
+    }
+    Some((numeral_to_int(p1.as_str()), numeral_to_int(p2.as_str())))
+}
+
+/// First and last digit of a calibration line
+#[derive(Debug, PartialEq, Eq)]
+pub struct Calibration {
+    /// Counting plain digits only, lines of the part 2 example may have none
+    digits: Option<(i64, i64)>,
+    /// Counting spelled-out digits as well
+    numerals: (i64, i64),
+}
+
+fn parse(lines: &[String]) -> Result<Vec<Calibration>> {
+    let digit_re = Regex::new(r"[1-9]").unwrap();
+    let numeral_re =
+        Regex::new(r"(1|2|3|4|5|6|7|8|9|one|two|three|four|five|six|seven|eight|nine)").unwrap();
+    util::parse_lines(lines, |line| {
+        Ok(Calibration {
+            digits: first_and_last(line, &digit_re),
+            numerals: first_and_last(line, &numeral_re)
+                .or_expected("a digit or spelled-out digit")?,
+        })
+    })
+}
+
+fn calibration_value((first, last): (i64, i64)) -> i64 {
+    10 * first + last
+}
+
+/// Lines without a plain digit only occur in the part 2 example and count as 0
+fn part1(input: &[Calibration]) -> Answer {
+    input
+        .iter()
+        .filter_map(|c| c.digits)
+        .map(calibration_value)
+        .sum::<i64>()
+        .into()
+}
+
+fn part2(input: &[Calibration]) -> Answer {
+    input
+        .iter()
+        .map(|c| calibration_value(c.numerals))
+        .sum::<i64>()
+        .into()
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<Calibration>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, input};
+
+    const WORDS: [&str; 9] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+
+    /// Lines with the same calibrations, spelling out digits only where needed
+    fn render(calibrations: &[Calibration]) -> Vec<String> {
+        let word = |n: i64| WORDS[n as usize - 1].to_string();
+        calibrations
+            .iter()
+            .map(|c| match c.digits {
+                None => word(c.numerals.0) + &word(c.numerals.1),
+                Some((first, last)) => {
+                    let mut line = String::new();
+                    if c.numerals.0 != first {
+                        line += &word(c.numerals.0);
+                    }
+                    line += &format!("{}{}", first, last);
+                    if c.numerals.1 != last {
+                        line += &word(c.numerals.1);
+                    }
+                    line
+                }
+            })
+            .collect()
+    }
+
+    /// Lines of letters and digits with at least one digit or spelled-out digit
+    fn document_text() -> impl Strategy<Value = Vec<String>> {
+        let numeral = prop_oneof![
+            (1..=9_u8).prop_map(|d| d.to_string()),
+            prop::sample::select(WORDS.to_vec()).prop_map(String::from),
+        ];
+        let line = ("[a-z1-9]{0,8}", numeral, "[a-z1-9]{0,8}")
+            .prop_map(|(before, numeral, after)| before + &numeral + &after);
+        prop::collection::vec(line, 0..8)
+    }
+
+    proptest! {
+        // Only the first and last digits are kept, so compare what a rendered document parses to
+        #[test]
+        fn parse_round_trip(lines in document_text()) {
+            let calibrations = parse(&lines).unwrap();
+            prop_assert_eq!(parse(&render(&calibrations)).unwrap(), calibrations);
+        }
+    }
+
+    // The example of part 2, with an extra line "1" that adds 11 to the published 281
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&input::<Day1>(1, InputVariant::Example)), 292.into());
+    }
+
+    #[test]
+    fn rejects_line_without_digit() {
+        let lines = ["1abc2", "pqrstu"].map(String::from);
+        let err = parse(&lines).unwrap_err().to_string();
+        assert_eq!(err, "<input>:2: expected a digit or spelled-out digit");
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day1>(1);
+    }
+}
@@ -0,0 +1,374 @@
+use grid::Grid;
+use log::debug;
+
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::util::{self, Direction, GridTile, Position};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Tile {
+    Ground,
+    NS,
+    WE,
+    NE,
+    NW,
+    SW,
+    SE,
+    Start,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum LoopCover {
+    Unknown,
+    In,
+    Out,
+}
+
+impl LoopCover {
+    fn opposite(&self) -> LoopCover {
+        match self {
+            LoopCover::In => LoopCover::Out,
+            LoopCover::Out => LoopCover::In,
+            LoopCover::Unknown => LoopCover::Unknown,
+        }
+    }
+}
+
+impl Tile {
+    fn connections(&self) -> [bool; 4] {
+        const F: bool = false;
+        const T: bool = true;
+        match self {
+            Tile::Ground => [F, F, F, F],
+            Tile::NS => [T, F, T, F],
+            Tile::WE => [F, T, F, T],
+            Tile::NE => [T, T, F, F],
+            Tile::NW => [T, F, F, T],
+            Tile::SW => [F, F, T, T],
+            Tile::SE => [F, T, T, F],
+            Tile::Start => [T, T, T, T],
+        }
+    }
+
+    fn connects_to(&self, next_tile: Tile, direction: Direction) -> bool {
+        let con_a = self.connections();
+        let con_b = next_tile.connections();
+
+        con_a[direction as usize] && con_b[direction.opposite() as usize]
+    }
+
+    fn repr(&self) -> char {
+        match self {
+            Tile::WE => '─',
+            Tile::NS => '│',
+            Tile::NW => '┘',
+            Tile::NE => '└',
+            Tile::SE => '┌',
+            Tile::SW => '┐',
+            Tile::Start => 'S',
+            Tile::Ground => ' ',
+        }
+    }
+}
+
+impl GridTile for Tile {
+    fn from_char(c: char) -> Result<Tile> {
+        Ok(match c {
+            '.' => Tile::Ground,
+            '-' => Tile::WE,
+            '|' => Tile::NS,
+            'L' => Tile::NE,
+            'J' => Tile::NW,
+            '7' => Tile::SW,
+            'F' => Tile::SE,
+            'S' => Tile::Start,
+            _ => return Err(Error::expected("a pipe tile (.-|LJ7FS)")),
+        })
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Tile::Ground => '.',
+            Tile::WE => '-',
+            Tile::NS => '|',
+            Tile::NE => 'L',
+            Tile::NW => 'J',
+            Tile::SW => '7',
+            Tile::SE => 'F',
+            Tile::Start => 'S',
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct GridEntry {
+    tile: Tile,
+    dist_from_start: usize,
+    loop_cover: LoopCover,
+    incoming_direction: Option<Direction>,
+    outgoing_direction: Option<Direction>,
+}
+
+/// Half of a pipe pointing in `dir`, to draw the loop directions in the debug output
+fn half_line(dir: Direction) -> char {
+    match dir {
+        Direction::N => '╵',
+        Direction::E => '╶',
+        Direction::S => '╷',
+        Direction::W => '╴',
+    }
+}
+
+fn print_grid(grid: &Grid<GridEntry>, elem_closure: fn(&GridEntry) -> char) {
+    debug!("{}", util::format_grid(grid, elem_closure));
+}
+
+/// Follow the loop from the start tile and classify all other tiles as inside or outside of it
+fn trace_loop(tiles: &Grid<Tile>) -> (Grid<GridEntry>, Position) {
+    let mut grid: Grid<GridEntry> = Grid::from_vec(
+        tiles
+            .iter()
+            .map(|&tile| GridEntry {
+                tile,
+                dist_from_start: 0,
+                loop_cover: LoopCover::Unknown,
+                incoming_direction: None,
+                outgoing_direction: None,
+            })
+            .collect(),
+        tiles.cols(),
+    );
+
+    let start_pos = grid
+        .indexed_iter()
+        .find(|(_, entry)| entry.tile == Tile::Start)
+        .map(|(grid_pos, _)| Position::from(grid_pos));
+    assert!(start_pos.is_some());
+
+    let mut next_pos = start_pos;
+
+    while next_pos.is_some() {
+        let pos = next_pos.take().unwrap();
+        let grid_entry = grid[pos.as_grid_pos()];
+
+        let check_dir = |dir: Direction| -> bool {
+            if grid_entry.incoming_direction == Some(dir) {
+                return false;
+            }
+            let new_pos = match pos.advance_in_grid(dir, &grid) {
+                None => return false,
+                Some(p) => p,
+            };
+            let new_grid_entry = &mut grid[new_pos.as_grid_pos()];
+            if grid_entry.tile.connects_to(new_grid_entry.tile, dir)
+                && new_grid_entry.dist_from_start == 0
+            {
+                new_grid_entry.dist_from_start = grid_entry.dist_from_start + 1;
+                new_grid_entry.incoming_direction = Some(dir.opposite());
+                if new_grid_entry.tile != Tile::Start {
+                    next_pos = Some(new_pos);
+                }
+                grid[pos.as_grid_pos()].outgoing_direction = Some(dir);
+                return true;
+            }
+            false
+        };
+        // Find connected adjacent tile
+        Direction::all().any(check_dir);
+    }
+
+    // Replace start tile
+    let start_entry = &mut grid[start_pos.unwrap().as_grid_pos()];
+    let (start_in, start_out) = (
+        start_entry.incoming_direction.unwrap(),
+        start_entry.outgoing_direction.unwrap(),
+    );
+    start_entry.tile = match start_in {
+        Direction::N => match start_out {
+            Direction::E => Tile::NE,
+            Direction::S => Tile::NS,
+            Direction::W => Tile::NW,
+            _ => panic!(),
+        },
+        Direction::E => match start_out {
+            Direction::N => Tile::NE,
+            Direction::S => Tile::SE,
+            Direction::W => Tile::WE,
+            _ => panic!(),
+        },
+        Direction::S => match start_out {
+            Direction::N => Tile::NS,
+            Direction::E => Tile::SE,
+            Direction::W => Tile::SW,
+            _ => panic!(),
+        },
+        Direction::W => match start_out {
+            Direction::N => Tile::NW,
+            Direction::E => Tile::WE,
+            Direction::S => Tile::SW,
+            _ => panic!(),
+        },
+    };
+
+    print_grid(&grid, |e| e.tile.repr());
+    print_grid(&grid, |e| match e.dist_from_start {
+        0 => '.',
+        _ => e.tile.repr(),
+    });
+    print_grid(&grid, |e| match e.incoming_direction {
+        None => '.',
+        Some(d) => half_line(d),
+    });
+    print_grid(&grid, |e| match e.outgoing_direction {
+        None => '.',
+        Some(d) => half_line(d),
+    });
+
+    fn get_vertical_direction(dir_a: Direction, dir_b: Direction) -> Direction {
+        match dir_a {
+            Direction::N | Direction::S => dir_a,
+            Direction::W | Direction::E => dir_b,
+        }
+    }
+
+    for row in 0..grid.rows() {
+        let mut cover = LoopCover::Out;
+        let mut bend_from_direction: Option<Direction> = None;
+        for col in grid.iter_row_mut(row) {
+            // Is part of loop?
+            if col.dist_from_start > 0 {
+                match col.tile {
+                    // Crossing straight pipe
+                    Tile::NS => cover = cover.opposite(),
+                    // Crossing begin of bend
+                    Tile::NE | Tile::SE => {
+                        bend_from_direction = Some(get_vertical_direction(
+                            col.outgoing_direction.unwrap(),
+                            col.incoming_direction.unwrap(),
+                        ))
+                    }
+                    // Crossing end of bend
+                    Tile::NW | Tile::SW => {
+                        // Check if bend opens up space behind it
+                        let vertical_direction = get_vertical_direction(
+                            col.outgoing_direction.unwrap(),
+                            col.incoming_direction.unwrap(),
+                        );
+                        if vertical_direction != bend_from_direction.unwrap() {
+                            cover = cover.opposite();
+                            bend_from_direction = None;
+                        }
+                    }
+                    Tile::WE => { // Ignored since we are traversing rows
+                    }
+                    _ => panic!(),
+                }
+            } else {
+                col.loop_cover = cover;
+            }
+        }
+    }
+
+    print_grid(&grid, |e| match e.loop_cover {
+        LoopCover::In => 'I',
+        LoopCover::Out => 'O',
+        _ => '.',
+    });
+
+    (grid, start_pos.unwrap())
+}
+
+fn parse(lines: &[String]) -> Result<Grid<Tile>> {
+    let tiles = util::build_grid(lines)?;
+    if tiles.iter().filter(|t| **t == Tile::Start).count() != 1 {
+        return Err(Error::expected("exactly one start tile 'S'"));
+    }
+    Ok(tiles)
+}
+
+fn part1(tiles: &Grid<Tile>) -> Answer {
+    let (grid, start_pos) = trace_loop(tiles);
+
+    let loop_len = grid[start_pos.as_grid_pos()].dist_from_start;
+    (loop_len / 2).into()
+}
+
+fn part2(tiles: &Grid<Tile>) -> Answer {
+    let (grid, _) = trace_loop(tiles);
+
+    let loop_area = grid
+        .iter()
+        .filter(|e| e.loop_cover == LoopCover::In)
+        .count();
+
+    loop_area.into()
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Grid<Tile>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, grid_text, input, input_lines, set_tile};
+
+    /// Pipe grids with a single start tile
+    fn pipes_text() -> impl Strategy<Value = Vec<String>> {
+        (
+            grid_text(".-|LJ7F"),
+            any::<prop::sample::Index>(),
+            any::<prop::sample::Index>(),
+        )
+            .prop_map(|(mut lines, row, col)| {
+                let row = row.index(lines.len());
+                set_tile(&mut lines, row, col, 'S');
+                lines
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in pipes_text()) {
+            prop_assert_eq!(util::render_grid(&parse(&lines).unwrap()), lines.join("\n"));
+        }
+    }
+
+    #[test]
+    fn grid_round_trip() {
+        let lines = input_lines(10, InputVariant::Example);
+        assert_eq!(util::render_grid(&parse(&lines).unwrap()), lines.join("\n"));
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&input::<Day10>(10, InputVariant::Example)), 80.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&input::<Day10>(10, InputVariant::Example)), 10.into());
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day10>(10);
+    }
+}
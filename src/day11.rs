@@ -1,36 +1,15 @@
-use std::{
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
-};
-
 use grid::Grid;
 use itertools::Itertools;
 use line_drawing::WalkGrid;
+use log::debug;
 
-// 00:41:57
-pub fn part1() {
-    let file = File::open(Path::new("inp11_2.txt")).unwrap();
-    let mut lines = io::BufReader::new(file).lines().peekable();
-    // FIXME is this OK?
-    let first_line = lines.peek().unwrap().as_ref().unwrap().clone();
-
-    let grid_vec: Vec<_> = lines
-        .flat_map(|l| l.unwrap().chars().map(|c| c == '#').collect::<Vec<_>>())
-        .collect();
-
-    let grid = Grid::from_vec(grid_vec, first_line.len());
+use crate::error::Result;
+use crate::solution::{Answer, Solution};
+use crate::util::{build_grid, render_grid, Position, Vector};
 
-    println!(
-        "{}",
-        grid.iter_rows()
-            .map(|row| String::from_iter(row.map(|c| match c {
-                false => '.',
-                true => '#',
-            })))
-            .collect::<Vec<String>>()
-            .join("\n")
-    );
+// 00:41:57
+fn part1(grid: &Grid<bool>) -> Answer {
+    debug!("{}", render_grid(grid));
 
     let mut galaxies = Vec::new();
     for (y, row) in grid.iter_rows().enumerate() {
@@ -40,7 +19,7 @@ pub fn part1() {
             }
         }
     }
-    println!("{:?}", galaxies);
+    debug!("{:?}", galaxies);
 
     let empty_rows: Vec<usize> = (0..grid.rows())
         .filter(|&y| grid.iter_row(y).all(|&c| !c))
@@ -58,7 +37,7 @@ pub fn part1() {
             .filter(|&&y| (y as i64) < galaxy.1)
             .count() as i64;
     }
-    println!("{:?}", galaxies);
+    debug!("{:?}", galaxies);
 
     //let galaxies = vec![(1, 6), (5, 11)];
 
@@ -72,42 +51,25 @@ pub fn part1() {
         })
         .sum();
 
-    println!("{}", sum / 2);
+    (sum / 2).into()
 }
 
 // 01:08:41
-pub fn part2() {
-    let file = File::open(Path::new("inp11_2.txt")).unwrap();
-    let mut lines = io::BufReader::new(file).lines().peekable();
-    // FIXME is this OK?
-    let first_line = lines.peek().unwrap().as_ref().unwrap().clone();
-
-    let grid_vec: Vec<_> = lines
-        .flat_map(|l| l.unwrap().chars().map(|c| c == '#').collect::<Vec<_>>())
-        .collect();
-
-    let grid = Grid::from_vec(grid_vec, first_line.len());
+fn part2(grid: &Grid<bool>) -> Answer {
+    galaxy_distance_sum(grid, 1_000_000).into()
+}
 
-    println!(
-        "{}",
-        grid.iter_rows()
-            .map(|row| String::from_iter(row.map(|c| match c {
-                false => '.',
-                true => '#',
-            })))
-            .collect::<Vec<String>>()
-            .join("\n")
-    );
+/// Sum of the distances between all pairs of galaxies when every empty row and column is
+/// `expansion` times as large
+fn galaxy_distance_sum(grid: &Grid<bool>, expansion: i64) -> i64 {
+    debug!("{}", render_grid(grid));
 
-    let mut galaxies = Vec::new();
-    for (y, row) in grid.iter_rows().enumerate() {
-        for (x, &col) in row.enumerate() {
-            if col {
-                galaxies.push((x as i64, y as i64));
-            }
-        }
-    }
-    println!("{:?}", galaxies);
+    let mut galaxies: Vec<Position> = grid
+        .indexed_iter()
+        .filter(|(_, &galaxy)| galaxy)
+        .map(|(pos, _)| pos.into())
+        .collect();
+    debug!("{:?}", galaxies);
 
     let empty_rows: Vec<usize> = (0..grid.rows())
         .filter(|&y| grid.iter_row(y).all(|&c| !c))
@@ -116,30 +78,81 @@ pub fn part2() {
         .filter(|&x| grid.iter_col(x).all(|&c| !c))
         .collect();
     for galaxy in &mut galaxies {
-        const EXPANSION: i64 = 999_999;
-        galaxy.0 += EXPANSION
-            * empty_cols
+        let empty_before = Vector {
+            x: empty_cols
                 .iter()
-                .filter(|&&x| (x as i64) < galaxy.0)
-                .count() as i64;
-        galaxy.1 += EXPANSION
-            * empty_rows
+                .filter(|&&x| (x as isize) < galaxy.x)
+                .count() as isize,
+            y: empty_rows
                 .iter()
-                .filter(|&&y| (y as i64) < galaxy.1)
-                .count() as i64;
+                .filter(|&&y| (y as isize) < galaxy.y)
+                .count() as isize,
+        };
+        *galaxy += empty_before * (expansion as isize - 1);
     }
-    println!("{:?}", galaxies);
+    debug!("{:?}", galaxies);
 
     let sum: i64 = galaxies
         .iter()
         .combinations(2)
-        .map(|galaxies| {
-            let &a = galaxies[0];
-            let &b = galaxies[1];
-
-            (b.0 - a.0).abs() + (b.1 - a.1).abs()
-        })
+        .map(|galaxies| galaxies[0].manhattan_distance_to(*galaxies[1]) as i64)
         .sum();
 
-    println!("{}", sum);
+    sum
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Grid<bool>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        build_grid(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, grid_text, input};
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in grid_text(".#")) {
+            let grid: Grid<bool> = build_grid(&lines).unwrap();
+            prop_assert_eq!(render_grid(&grid), lines.join("\n"));
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(
+            part1(&input::<Day11>(11, InputVariant::Example)),
+            374.into()
+        );
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(
+            part2(&input::<Day11>(11, InputVariant::Example)),
+            82000210.into()
+        );
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day11>(11);
+    }
 }
@@ -1,41 +1,49 @@
-use std::{
-    collections::{HashMap, VecDeque},
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
-};
+use std::collections::{HashMap, VecDeque};
 
 use itertools::Itertools;
+use log::trace;
 
-pub fn part1() {
-    let file = File::open(Path::new("inp12_2.txt")).unwrap();
-    let lines = io::BufReader::new(file).lines().peekable();
-
-    const OK: i8 = 0;
-    const DAMAGED: i8 = 1;
-    const UNKNOWN: i8 = -1;
-
-    let spring_combinations = lines.map(|l| {
-        // FIXME why do I have to write this?
-        let l_unwrap = l.unwrap();
-        let mut line_parts = l_unwrap.split_whitespace();
-        let spring = line_parts
-            .next()
-            .unwrap()
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::util;
+
+const OK: i8 = 0;
+const DAMAGED: i8 = 1;
+const UNKNOWN: i8 = -1;
+
+type Spring = Vec<i8>;
+
+/// Condition of every spring and the sizes of the contiguous damaged groups
+type Record = (Spring, Vec<usize>);
+
+fn parse(lines: &[String]) -> Result<Vec<Record>> {
+    util::parse_lines(lines, |l| {
+        let (spring_str, groups_str) = l
+            .split_once(' ')
+            .ok_or_else(|| Error::expected("'<springs> <group sizes>'"))?;
+        let spring = spring_str
             .chars()
-            .map(|c| match c {
-                '.' => OK,
-                '#' => DAMAGED,
-                '?' => UNKNOWN,
-                _ => panic!(),
+            .enumerate()
+            .map(|(col, c)| match c {
+                '.' => Ok(OK),
+                '#' => Ok(DAMAGED),
+                '?' => Ok(UNKNOWN),
+                _ => Err(Error::expected("'.', '#' or '?'").at_column(col)),
             })
-            .collect_vec();
-        let grouped_record = line_parts
-            .next()
-            .unwrap()
+            .collect::<Result<_>>()?;
+        let grouped_record = groups_str
             .split(',')
-            .map(|r| r.parse().unwrap())
-            .collect_vec();
+            .map(|r| {
+                r.parse()
+                    .map_err(|_| Error::expected_at(l, r, "a group size"))
+            })
+            .collect::<Result<_>>()?;
+        Ok((spring, grouped_record))
+    })
+}
+
+fn part1(records: &[Record]) -> Answer {
+    let spring_combinations = records.iter().map(|(spring, grouped_record)| {
         let open_positions = spring
             .iter()
             .enumerate()
@@ -69,24 +77,17 @@ pub fn part1() {
                 if this_group_size > 0 {
                     this_grouped_record.push(this_group_size);
                 }
-                this_grouped_record == grouped_record
+                this_grouped_record == *grouped_record
             })
             .count()
     });
     let sum: usize = spring_combinations.sum();
-    println!("{}", sum);
+    sum.into()
 }
 
-pub fn part2() {
-    let file = File::open(Path::new("inp12_2.txt")).unwrap();
-    let lines = io::BufReader::new(file).lines().collect_vec();
-
-    const OK: i8 = 0;
-    const DAMAGED: i8 = 1;
-    const UNKNOWN: i8 = -1;
-
+fn part2(records: &[Record]) -> Answer {
     fn print_entry(entry: &QueueEntry) {
-        println!(
+        trace!(
             "{} | {:?}",
             entry
                 .spring
@@ -102,7 +103,6 @@ pub fn part2() {
         );
     }
 
-    type Spring = Vec<i8>;
     type CacheKey = (Spring, Vec<usize>);
     type CacheEntry = (usize, bool);
 
@@ -120,29 +120,15 @@ pub fn part2() {
 
     let mut cache: HashMap<CacheKey, CacheEntry> = HashMap::new();
 
-    let spring_combinations = lines
+    let spring_combinations = records
         .iter()
         //.skip(1)
         //.take(1)
         //.par_iter()
         //.progress()
-        .map(|l| {
-            println!("start {}", iteri);
+        .map(|(orig_spring, orig_grouped_record)| {
+            trace!("start {}", iteri);
             iteri += 1;
-            // FIXME why do I have to write this? why as_ref()?
-            let l_unwrap = l.as_ref().unwrap();
-            let mut line_parts = l_unwrap.split_whitespace();
-            let orig_spring = line_parts
-                .next()
-                .unwrap()
-                .chars()
-                .map(|c| match c {
-                    '.' => OK,
-                    '#' => DAMAGED,
-                    '?' => UNKNOWN,
-                    _ => panic!(),
-                })
-                .collect_vec();
             let mut spring = orig_spring.clone();
             for _ in 0..4 {
                 let mut spring_copy = orig_spring.clone();
@@ -152,12 +138,6 @@ pub fn part2() {
             // Let "DAMAGED" counting loop always finish with OK
             spring.push(OK);
 
-            let orig_grouped_record = line_parts
-                .next()
-                .unwrap()
-                .split(',')
-                .map(|r| r.parse().unwrap())
-                .collect_vec();
             let mut grouped_record = orig_grouped_record.clone();
             for _ in 0..4 {
                 let mut record_copy = orig_grouped_record.clone();
@@ -208,7 +188,7 @@ pub fn part2() {
                     cache.get(&(spring.to_vec(), entry.groups_to_assign.clone()))
                 {
                     if cfg!(debug_assertions) {
-                        println!("!!OK!! cache: {}", cache_entry.0);
+                        trace!("!!OK!! cache: {}", cache_entry.0);
                     }
                     assert!(cache_entry.1);
                     ok_combinations += cache_entry.0;
@@ -228,9 +208,9 @@ pub fn part2() {
                         }
 
                         let mut add_finisher = || {
-                            println!("--- OK ---");
+                            trace!("--- OK ---");
                             print_entry(&entry);
-                            println!("----------\n");
+                            trace!("----------\n");
                             // Finished
                             ok_combinations += 1;
                             // Update cache
@@ -399,9 +379,9 @@ pub fn part2() {
                             continue;
                         }
                         if cfg!(debug_assertions) {
-                            println!("OK: ///");
+                            trace!("OK: ///");
                             print_entry(&entry);
-                            println!("    \\\\\\");
+                            trace!("    \\\\\\");
                         }
                         ok_combinations += 1;
                         // Update cache
@@ -427,7 +407,7 @@ pub fn part2() {
                 }
             }
 
-            println!(
+            trace!(
                 "====== {} / {:?}",
                 ok_combinations,
                 cache
@@ -447,6 +427,82 @@ pub fn part2() {
             ok_combinations
         });
     let sum: usize = spring_combinations.sum();
-    println!("[4780439962549] [4795153189721]");
-    println!(" {}", sum);
+    sum.into()
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Vec<Record>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, input};
+
+    fn render(records: &[Record]) -> Vec<String> {
+        records
+            .iter()
+            .map(|(spring, grouped_record)| {
+                let springs = spring.iter().map(|&s| match s {
+                    OK => '.',
+                    DAMAGED => '#',
+                    _ => '?',
+                });
+                format!(
+                    "{} {}",
+                    String::from_iter(springs),
+                    grouped_record.iter().join(",")
+                )
+            })
+            .collect()
+    }
+
+    fn records_text() -> impl Strategy<Value = Vec<String>> {
+        let groups = prop::collection::vec(1..10_usize, 1..6);
+        let record = ("[.#?]{1,20}", groups)
+            .prop_map(|(springs, groups)| format!("{} {}", springs, groups.iter().join(",")));
+        prop::collection::vec(record, 0..8)
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in records_text()) {
+            prop_assert_eq!(render(&parse(&lines).unwrap()), lines);
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&input::<Day12>(12, InputVariant::Example)), 21.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(
+            part2(&input::<Day12>(12, InputVariant::Example)),
+            525152.into()
+        );
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day12>(12);
+    }
 }
@@ -1,38 +1,77 @@
-use std::{
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
-};
-
 use grid::Grid;
 use itertools::Itertools;
+use log::trace;
+
+use crate::error::Result;
+use crate::solution::{Answer, Solution};
+use crate::util::{build_grid, render_grid};
+
+fn parse(lines: &[String]) -> Result<Vec<Grid<bool>>> {
+    let mut grids = Vec::new();
+    let mut first_line = 0;
+    for pattern in lines.split(|l| l.is_empty()) {
+        if !pattern.is_empty() {
+            let grid = build_grid(pattern).map_err(|e| e.offset_lines(first_line))?;
+            grids.push(grid);
+        }
+        first_line += pattern.len() + 1;
+    }
+    Ok(grids)
+}
+
+fn part1(grids: &[Grid<bool>]) -> Answer {
+    let mut sum = 0;
+
+    for grid in grids {
+        let mut grid = grid.clone();
 
-pub fn part1() {
-    let file = File::open(Path::new("inp13_2.txt")).unwrap();
-    let lines = io::BufReader::new(file).lines();
+        trace!("{}", render_grid(&grid));
 
-    let mut grid_vec: Vec<bool> = Vec::new();
-    let mut grid_w = 0;
+        fn find_reflection(grid: &Grid<bool>) -> Option<usize> {
+            let reflection_line = (0..grid.rows() - 1).find(|&mirror_at| {
+                (0..=mirror_at).all(|check_y| {
+                    //println!("== mirror_at {} check_y {}", mirror_at, check_y);
+                    let y_a = mirror_at + check_y + 1;
+                    if y_a >= grid.rows() {
+                        //println!("exita");
+                        return true;
+                    }
+                    let y_b = match mirror_at.checked_sub(check_y) {
+                        None => return true,
+                        Some(y) => y,
+                    };
+                    //println!(" -> y_a {} y_b {}", y_a, y_b);
+                    let row_a = grid.iter_row(y_a);
+                    let row_b = grid.iter_row(y_b);
+                    row_a.eq(row_b)
+                })
+            });
+            trace!("- {:?}", reflection_line);
+            reflection_line.map(|l| l + 1)
+        }
+
+        sum += match find_reflection(&grid) {
+            Some(l) => 100 * l,
+            None => {
+                trace!("transpose");
+                grid.transpose();
+                find_reflection(&grid).unwrap()
+            }
+        };
+    }
+
+    sum.into()
+}
+
+fn part2(grids: &[Grid<bool>]) -> Answer {
     let mut sum = 0;
 
-    for l in lines {
-        let line = l.unwrap();
-        if line.is_empty() {
-            let mut grid = Grid::from_vec(grid_vec, grid_w);
-
-            println!(
-                "{}",
-                grid.iter_rows()
-                    .map(|row| String::from_iter(row.map(|c| match c {
-                        false => '.',
-                        true => '#',
-                    })))
-                    .collect::<Vec<String>>()
-                    .join("\n")
-            );
-
-            fn find_reflection(grid: &Grid<bool>) -> Option<usize> {
-                let reflection_line = (0..grid.rows() - 1).find(|&mirror_at| {
+    for grid in grids {
+        trace!("{}", render_grid(grid));
+
+        fn find_reflections(grid: &Grid<bool>) -> Vec<usize> {
+            (0..grid.rows() - 1)
+                .filter(|&mirror_at| {
                     (0..=mirror_at).all(|check_y| {
                         //println!("== mirror_at {} check_y {}", mirror_at, check_y);
                         let y_a = mirror_at + check_y + 1;
@@ -49,171 +88,146 @@ pub fn part1() {
                         let row_b = grid.iter_row(y_b);
                         row_a.eq(row_b)
                     })
-                });
-                println!("- {:?}", reflection_line);
-                reflection_line.map(|l| l + 1)
+                })
+                .map(|l| l + 1)
+                .collect_vec()
+            //println!("- {:?}", reflection_line);
+        }
+
+        let y_reflections = find_reflections(grid);
+        let mut orig_reflection_line: Option<usize> = None;
+        if y_reflections.len() == 1 {
+            orig_reflection_line = Some(y_reflections[0] * 100);
+        } else if y_reflections.is_empty() {
+            trace!("transpose");
+            let mut transp_grid = grid.clone();
+            transp_grid.transpose();
+            let x_reflections = find_reflections(&transp_grid);
+            if x_reflections.len() == 1 {
+                orig_reflection_line = Some(x_reflections[0]);
             }
+        }
+        /*
+        grid.iter_mut().find(|elem| {
+            // this not work
+            *elem = !**elem;
+            true
+        });
+        */
+        let orig_reflection_line = orig_reflection_line.unwrap();
+        trace!("old: {}", orig_reflection_line);
+
+        let mut new_reflection_line: Option<usize> = None;
+        'outer: for y in 0..grid.rows() {
+            for x in 0..grid.cols() {
+                let mut new_grid = grid.clone();
+                new_grid[(y, x)] = !new_grid[(y, x)];
+                if y == 6 && x == 16 {
+                    trace!("////////////////");
+                    trace!("{}", render_grid(&new_grid));
+                    trace!("-----------------");
+                }
 
-            sum += match find_reflection(&grid) {
-                Some(l) => 100 * l,
-                None => {
-                    println!("transpose");
-                    grid.transpose();
-                    find_reflection(&grid).unwrap()
+                let y_reflections = find_reflections(&new_grid);
+                if let Some(new_y_reflection) = y_reflections
+                    .iter()
+                    .map(|l| l * 100)
+                    .find(|&r| r != orig_reflection_line)
+                {
+                    new_reflection_line = Some(new_y_reflection);
+                    break 'outer;
                 }
-            };
 
-            grid_vec = Vec::new();
-            continue;
-        }
+                new_grid.transpose();
 
-        grid_w = line.len();
-        grid_vec.extend(line.chars().map(|c| c == '#'));
+                let x_reflections = find_reflections(&new_grid);
+                if let Some(&new_x_reflection) =
+                    x_reflections.iter().find(|&&r| r != orig_reflection_line)
+                {
+                    new_reflection_line = Some(new_x_reflection);
+                    break 'outer;
+                }
+
+                if y == 6 && x == 16 {
+                    trace!("////////////////");
+                    trace!("{}", render_grid(&new_grid));
+                    trace!("-----------------");
+                }
+            }
+        }
+        trace!("new: {:?}", new_reflection_line);
+        sum += new_reflection_line.unwrap();
     }
 
-    println!("{}", sum);
+    sum.into()
 }
 
-pub fn part2() {
-    let file = File::open(Path::new("inp13_2.txt")).unwrap();
-    let lines = io::BufReader::new(file).lines();
+pub struct Day13;
 
-    let mut grid_vec: Vec<bool> = Vec::new();
-    let mut grid_w = 0;
-    let mut sum = 0;
+impl Solution for Day13 {
+    type Input = Vec<Grid<bool>>;
 
-    for l in lines {
-        let line = l.unwrap();
-        if line.is_empty() {
-            let grid = Grid::from_vec(grid_vec, grid_w);
-
-            println!(
-                "{}",
-                grid.iter_rows()
-                    .map(|row| String::from_iter(row.map(|c| match c {
-                        false => '.',
-                        true => '#',
-                    })))
-                    .collect::<Vec<String>>()
-                    .join("\n")
-            );
-
-            fn find_reflections(grid: &Grid<bool>) -> Vec<usize> {
-                (0..grid.rows() - 1)
-                    .filter(|&mirror_at| {
-                        (0..=mirror_at).all(|check_y| {
-                            //println!("== mirror_at {} check_y {}", mirror_at, check_y);
-                            let y_a = mirror_at + check_y + 1;
-                            if y_a >= grid.rows() {
-                                //println!("exita");
-                                return true;
-                            }
-                            let y_b = match mirror_at.checked_sub(check_y) {
-                                None => return true,
-                                Some(y) => y,
-                            };
-                            //println!(" -> y_a {} y_b {}", y_a, y_b);
-                            let row_a = grid.iter_row(y_a);
-                            let row_b = grid.iter_row(y_b);
-                            row_a.eq(row_b)
-                        })
-                    })
-                    .map(|l| l + 1)
-                    .collect_vec()
-                //println!("- {:?}", reflection_line);
-            }
-
-            let y_reflections = find_reflections(&grid);
-            let mut orig_reflection_line: Option<usize> = None;
-            if y_reflections.len() == 1 {
-                orig_reflection_line = Some(y_reflections[0] * 100);
-            } else if y_reflections.is_empty() {
-                println!("transpose");
-                let mut transp_grid = grid.clone();
-                transp_grid.transpose();
-                let x_reflections = find_reflections(&transp_grid);
-                if x_reflections.len() == 1 {
-                    orig_reflection_line = Some(x_reflections[0]);
-                }
-            }
-            /*
-            grid.iter_mut().find(|elem| {
-                // this not work
-                *elem = !**elem;
-                true
-            });
-            */
-            let orig_reflection_line = orig_reflection_line.unwrap();
-            println!("old: {}", orig_reflection_line);
-
-            let mut new_reflection_line: Option<usize> = None;
-            'outer: for y in 0..grid.rows() {
-                for x in 0..grid.cols() {
-                    let mut new_grid = grid.clone();
-                    new_grid[(y, x)] = !new_grid[(y, x)];
-                    if y == 6 && x == 16 {
-                        println!("////////////////");
-                        println!(
-                            "{}",
-                            new_grid
-                                .iter_rows()
-                                .map(|row| String::from_iter(row.map(|c| match c {
-                                    false => '.',
-                                    true => '#',
-                                })))
-                                .collect::<Vec<String>>()
-                                .join("\n")
-                        );
-                        println!("-----------------");
-                    }
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
 
-                    let y_reflections = find_reflections(&new_grid);
-                    if let Some(new_y_reflection) = y_reflections
-                        .iter()
-                        .map(|l| l * 100)
-                        .find(|&r| r != orig_reflection_line)
-                    {
-                        new_reflection_line = Some(new_y_reflection);
-                        break 'outer;
-                    }
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
 
-                    new_grid.transpose();
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
 
-                    let x_reflections = find_reflections(&new_grid);
-                    if let Some(&new_x_reflection) =
-                        x_reflections.iter().find(|&&r| r != orig_reflection_line)
-                    {
-                        new_reflection_line = Some(new_x_reflection);
-                        break 'outer;
-                    }
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, grid_text, input};
+
+    fn render(grids: &[Grid<bool>]) -> Vec<String> {
+        grids
+            .iter()
+            .map(render_grid)
+            .join("\n\n")
+            .lines()
+            .map(String::from)
+            .collect()
+    }
 
-                    if y == 6 && x == 16 {
-                        println!("////////////////");
-                        println!(
-                            "{}",
-                            new_grid
-                                .iter_rows()
-                                .map(|row| String::from_iter(row.map(|c| match c {
-                                    false => '.',
-                                    true => '#',
-                                })))
-                                .collect::<Vec<String>>()
-                                .join("\n")
-                        );
-                        println!("-----------------");
-                    }
-                }
-            }
-            println!("new: {:?}", new_reflection_line);
-            sum += new_reflection_line.unwrap();
+    /// Patterns separated by empty lines
+    fn patterns_text() -> impl Strategy<Value = Vec<String>> {
+        prop::collection::vec(grid_text(".#"), 0..5).prop_map(|grids| grids.join(&String::new()))
+    }
 
-            grid_vec = Vec::new();
-            continue;
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in patterns_text()) {
+            prop_assert_eq!(render(&parse(&lines).unwrap()), lines);
         }
+    }
 
-        grid_w = line.len();
-        grid_vec.extend(line.chars().map(|c| c == '#'));
+    #[test]
+    fn part1_example() {
+        assert_eq!(
+            part1(&input::<Day13>(13, InputVariant::Example)),
+            405.into()
+        );
     }
 
-    println!("{}", sum);
+    #[test]
+    fn part2_example() {
+        assert_eq!(
+            part2(&input::<Day13>(13, InputVariant::Example)),
+            400.into()
+        );
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day13>(13);
+    }
 }
@@ -1,33 +1,31 @@
 use std::{
-    collections::BTreeMap,
+    collections::{btree_map::Entry, BTreeMap},
     hash::{DefaultHasher, Hasher},
 };
 
-use super::util;
+use crate::error::{Error, Result};
+use crate::repl::{step_count, CommandResult, Explore};
+use crate::solution::{Answer, Solution};
+use crate::util::{build_grid_with, format_grid, Direction};
 use grid::Grid;
+use log::{debug, trace};
 
 const CUBE: u8 = 1;
 const EMPTY: u8 = 0;
 const ROUND: u8 = 2;
 
+fn parse(lines: &[String]) -> Result<Grid<u8>> {
+    build_grid_with(lines, |c| match c {
+        '#' => Ok(CUBE),
+        'O' => Ok(ROUND),
+        '.' => Ok(EMPTY),
+        _ => Err(Error::expected("'#', 'O' or '.'")),
+    })
+}
+
 // 00:24:32
-pub fn part1() {
-    let lines = util::file_lines("inp14_2.txt");
-    let first_line = &lines[0];
-    let grid_vec: Vec<_> = lines
-        .iter()
-        .flat_map(|l| {
-            l.chars()
-                .map(|c| match c {
-                    '#' => CUBE,
-                    'O' => ROUND,
-                    '.' => EMPTY,
-                    _ => panic!(),
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect();
-    let mut grid = Grid::from_vec(grid_vec, first_line.len());
+fn part1(grid: &Grid<u8>) -> Answer {
+    let mut grid = grid.clone();
 
     for y in 0..grid.rows() {
         for x in 0..grid.cols() {
@@ -50,63 +48,76 @@ pub fn part1() {
         }
     }
 
-    let sum: usize = grid
-        .indexed_iter()
+    north_load(&grid).into()
+}
+
+/// Load on the north support beams
+fn north_load(grid: &Grid<u8>) -> usize {
+    grid.indexed_iter()
         .map(|((y, _), &c)| if c == ROUND { grid.rows() - y } else { 0 })
-        .sum();
-    println!("{}", sum);
+        .sum()
 }
 
-// 00:51:13
-pub fn part2() {
-    let lines = util::file_lines("inp14_2.txt");
-    let first_line = &lines[0];
-    let grid_vec: Vec<_> = lines
-        .iter()
-        .flat_map(|l| {
-            l.chars()
-                .map(|c| match c {
-                    '#' => CUBE,
-                    'O' => ROUND,
-                    '.' => EMPTY,
-                    _ => panic!(),
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect();
-    let mut grid = Grid::from_vec(grid_vec, first_line.len());
-
-    fn fall_north(grid: &mut Grid<u8>) {
-        for y in 1..grid.rows() {
-            for x in 0..grid.cols() {
-                if grid[(y, x)] != ROUND {
-                    continue;
-                }
-                let move_to_y = num::range_step_inclusive((y - 1) as isize, 0, -1)
-                    .take_while(|&y2| grid[(y2 as usize, x)] == EMPTY)
-                    .last();
-                if let Some(move_to_y) = move_to_y {
-                    grid[(y, x)] = EMPTY;
-                    grid[(move_to_y as usize, x)] = ROUND;
-                }
+fn fall_north(grid: &mut Grid<u8>) {
+    for y in 1..grid.rows() {
+        for x in 0..grid.cols() {
+            if grid[(y, x)] != ROUND {
+                continue;
+            }
+            let move_to_y = num::range_step_inclusive((y - 1) as isize, 0, -1)
+                .take_while(|&y2| grid[(y2 as usize, x)] == EMPTY)
+                .last();
+            if let Some(move_to_y) = move_to_y {
+                grid[(y, x)] = EMPTY;
+                grid[(move_to_y as usize, x)] = ROUND;
             }
         }
     }
+}
 
-    fn print_grid(grid: &Grid<u8>) {
-        println!(
-            "{}",
-            grid.iter_rows()
-                .map(|row| String::from_iter(row.map(|&c| match c {
-                    CUBE => '#',
-                    ROUND => 'O',
-                    EMPTY => '.',
-                    _ => panic!(),
-                })))
-                .collect::<Vec<String>>()
-                .join("\n")
-        );
+/// Let all round rocks roll towards `dir`, by turning the grid so that `dir` is north
+fn tilt(grid: &mut Grid<u8>, dir: Direction) {
+    match dir {
+        Direction::N => fall_north(grid),
+        Direction::W => {
+            grid.transpose();
+            fall_north(grid);
+            grid.transpose();
+        }
+        Direction::S => {
+            grid.flip_rows();
+            fall_north(grid);
+            grid.flip_rows();
+        }
+        Direction::E => {
+            grid.flip_cols();
+            grid.transpose();
+            fall_north(grid);
+            grid.transpose();
+            grid.flip_cols();
+        }
     }
+}
+
+fn spin_cycle(grid: &mut Grid<u8>) {
+    for dir in [Direction::N, Direction::W, Direction::S, Direction::E] {
+        tilt(grid, dir);
+    }
+}
+
+fn tile_char(&c: &u8) -> char {
+    match c {
+        CUBE => '#',
+        ROUND => 'O',
+        EMPTY => '.',
+        _ => panic!(),
+    }
+}
+
+// 00:51:13
+fn part2(grid: &Grid<u8>) -> Answer {
+    let mut grid = grid.clone();
+
     fn grid_hash(grid: &Grid<u8>) -> u64 {
         let mut hasher = DefaultHasher::new();
         for ((y, x), _) in grid.indexed_iter().filter(|(_, &c)| c == ROUND) {
@@ -118,41 +129,129 @@ pub fn part2() {
     let mut grid_hashes_map = BTreeMap::new();
     let mut i = 0;
     while i < 1000000000 {
-        println!("- {}", i);
+        trace!("- {}\n{}", i, format_grid(&grid, tile_char));
         let i_hash = grid_hash(&grid);
-        if let Err(insert_err) = grid_hashes_map.try_insert(i_hash, i) {
-            let cycle_begin = insert_err.entry.get();
-            let cycle_length = i - cycle_begin;
-            println!("cycle at {} to {}, length {}", cycle_begin, i, cycle_length);
-            // Skip to end
-            i += (1000000000 - i) / cycle_length * cycle_length;
-            grid_hashes_map.clear();
-            continue;
+        match grid_hashes_map.entry(i_hash) {
+            Entry::Occupied(entry) => {
+                let cycle_begin = *entry.get();
+                let cycle_length = i - cycle_begin;
+                debug!("cycle at {} to {}, length {}", cycle_begin, i, cycle_length);
+                // Skip to end
+                i += (1000000000 - i) / cycle_length * cycle_length;
+                grid_hashes_map.clear();
+                continue;
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(i);
+            }
         }
 
-        // N
-        fall_north(&mut grid);
-        // W
-        grid.transpose();
-        fall_north(&mut grid);
-        grid.transpose();
-        // S
-        grid.flip_rows();
-        fall_north(&mut grid);
-        grid.flip_rows();
-        // E
-        grid.flip_cols();
-        grid.transpose();
-        fall_north(&mut grid);
-        grid.transpose();
-        grid.flip_cols();
-
+        spin_cycle(&mut grid);
         i += 1;
     }
 
-    let sum: usize = grid
-        .indexed_iter()
-        .map(|((y, _), &c)| if c == ROUND { grid.rows() - y } else { 0 })
-        .sum();
-    println!("{}", sum);
+    north_load(&grid).into()
+}
+
+/// Platform tilted step by step in the REPL
+struct Platform {
+    grid: Grid<u8>,
+    cycles: usize,
+}
+
+impl Explore for Platform {
+    fn commands(&self) -> &[(&'static str, &'static str)] {
+        &[
+            ("grid", "Show the platform"),
+            ("step [N]", "Run N spin cycles"),
+            ("tilt <N|E|S|W>", "Tilt the platform once"),
+            ("load", "Show the load on the north support beams"),
+        ]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Option<CommandResult> {
+        let result = match (command, args) {
+            ("grid", []) => Ok(format_grid(&self.grid, tile_char)),
+            ("step", args) => step_count(args).map(|count| {
+                for _ in 0..count {
+                    spin_cycle(&mut self.grid);
+                }
+                self.cycles += count;
+                format!("{} spin cycles done", self.cycles)
+            }),
+            ("tilt", [dir]) => {
+                let dir = match *dir {
+                    "N" | "n" => Direction::N,
+                    "E" | "e" => Direction::E,
+                    "S" | "s" => Direction::S,
+                    "W" | "w" => Direction::W,
+                    _ => return Some(Err(format!("Expected N, E, S or W, not '{}'", dir))),
+                };
+                tilt(&mut self.grid, dir);
+                Ok(format_grid(&self.grid, tile_char))
+            }
+            ("load", []) => Ok(north_load(&self.grid).to_string()),
+            _ => return None,
+        };
+        Some(result)
+    }
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Grid<u8>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+
+    fn explore(input: &Self::Input) -> Option<Box<dyn Explore>> {
+        Some(Box::new(Platform {
+            grid: input.clone(),
+            cycles: 0,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, grid_text, input};
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in grid_text("#O.")) {
+            prop_assert_eq!(format_grid(&parse(&lines).unwrap(), tile_char), lines.join("\n"));
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(
+            part1(&input::<Day14>(14, InputVariant::Example)),
+            136.into()
+        );
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&input::<Day14>(14, InputVariant::Example)), 64.into());
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day14>(14);
+    }
 }
@@ -1,26 +1,51 @@
 use itertools::Itertools;
 
-use super::util;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
 
-fn hash(input: &str) -> u8 {
-    input.chars().fold(0_u8, |acc, c| {
-        (((acc as u64) + c.as_ascii().unwrap().to_u8() as u64) * 17 % 256) as u8
-    })
+/// HASH of `input`, which `parse` has checked to be ASCII so that bytes are characters
+pub fn hash(input: &str) -> u8 {
+    input
+        .bytes()
+        .fold(0_u8, |acc, c| (((acc as u64) + c as u64) * 17 % 256) as u8)
+}
+
+fn parse(lines: &[String]) -> Result<Vec<String>> {
+    let line = lines
+        .first()
+        .ok_or_else(|| Error::expected("an initialization sequence"))?;
+    if let Some(col) = line.chars().position(|c| !c.is_ascii()) {
+        return Err(Error::expected("ASCII characters")
+            .in_line(0)
+            .at_column(col));
+    }
+    line.split(',')
+        .map(|step| {
+            let valid = match step.split_once('=') {
+                Some((_, focal_length)) => focal_length.parse::<u8>().is_ok(),
+                None => step.ends_with('-'),
+            };
+            match valid {
+                true => Ok(step.to_string()),
+                false => Err(Error::expected_at(
+                    line,
+                    step,
+                    "'<label>-' or '<label>=<focal length>'",
+                )),
+            }
+        })
+        .collect::<Result<_>>()
+        .map_err(|e| e.in_line(0))
 }
 
 // 00:11:28
-pub fn part1() {
-    let lines = util::file_lines("inp15_2.txt");
-    let instructions = lines[0].split(',');
-    let sum: u64 = instructions.map(|i| hash(i) as u64).sum();
-    println!("{}", sum);
+fn part1(instructions: &[String]) -> Answer {
+    let sum: u64 = instructions.iter().map(|i| hash(i) as u64).sum();
+    sum.into()
 }
 
 // 00:40:34
-pub fn part2() {
-    let lines = util::file_lines("inp15_2.txt");
-    let instructions = lines[0].split(',');
-
+fn part2(instructions: &[String]) -> Answer {
     #[derive(Debug, PartialEq, Eq, Clone)]
     struct Slot {
         lens_label: String,
@@ -70,5 +95,73 @@ pub fn part2() {
         })
         .sum::<usize>();
 
-    println!("{}", sum);
+    sum.into()
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<String>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, input};
+
+    fn render(steps: &[String]) -> Vec<String> {
+        vec![steps.join(",")]
+    }
+
+    fn sequence_text() -> impl Strategy<Value = Vec<String>> {
+        let operation = prop_oneof![
+            Just("-".to_string()),
+            (0..=u8::MAX).prop_map(|focal_length| format!("={}", focal_length)),
+        ];
+        let step = ("[a-z]{1,6}", operation).prop_map(|(label, operation)| label + &operation);
+        prop::collection::vec(step, 1..10).prop_map(|steps| render(&steps))
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in sequence_text()) {
+            prop_assert_eq!(render(&parse(&lines).unwrap()), lines);
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(
+            part1(&input::<Day15>(15, InputVariant::Example)),
+            1320.into()
+        );
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(
+            part2(&input::<Day15>(15, InputVariant::Example)),
+            145.into()
+        );
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day15>(15);
+    }
 }
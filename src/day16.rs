@@ -1,9 +1,11 @@
 use std::{cmp::max, collections::VecDeque};
 
 use grid::Grid;
+use log::debug;
 
-use super::util;
-use super::util::{Direction, Position};
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::util::{build_grid, format_grid, render_grid, Direction, GridTile, Position};
 
 #[derive(Clone, Copy, Debug)]
 #[repr(u8)]
@@ -14,60 +16,53 @@ enum TileType {
     MirrorCw = b'/',
     MirrorCcw = b'\\',
 }
-impl From<char> for TileType {
-    fn from(c: char) -> Self {
-        // FIXME
+impl GridTile for TileType {
+    fn from_char(c: char) -> Result<Self> {
         match c {
-            '.' => TileType::Empty,
-            '|' => TileType::SplitV,
-            '-' => TileType::SplitH,
-            '/' => TileType::MirrorCw,
-            '\\' => TileType::MirrorCcw,
-            _ => panic!(),
+            '.' => Ok(TileType::Empty),
+            '|' => Ok(TileType::SplitV),
+            '-' => Ok(TileType::SplitH),
+            '/' => Ok(TileType::MirrorCw),
+            '\\' => Ok(TileType::MirrorCcw),
+            _ => Err(Error::expected("a tile (.|-/\\)")),
         }
     }
-}
-impl From<TileType> for char {
-    fn from(val: TileType) -> Self {
-        val as u8 as char
+
+    fn to_char(&self) -> char {
+        *self as u8 as char
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-struct Tile {
+pub struct Tile {
     tile_type: TileType,
     energized: bool,
     energized_dir: [bool; 4],
 }
 
-pub fn part1() {
-    let lines = util::file_lines("inp16_2.txt");
-
-    let first_line = &lines[0];
-    let grid_vec: Vec<_> = lines
-        .iter()
-        .flat_map(|l| {
-            l.chars()
-                .map(|c| Tile {
-                    tile_type: c.into(),
-                    energized: false,
-                    energized_dir: [false, false, false, false],
-                })
-                .collect::<Vec<_>>()
+/// Not yet energized
+impl GridTile for Tile {
+    fn from_char(c: char) -> Result<Self> {
+        Ok(Tile {
+            tile_type: TileType::from_char(c)?,
+            energized: false,
+            energized_dir: [false; 4],
         })
-        .collect();
-    let mut grid = Grid::from_vec(grid_vec, first_line.len());
-
-    fn print_grid(grid: &Grid<Tile>) {
-        println!(
-            "{}",
-            grid.iter_rows()
-                .map(|row| String::from_iter(row.map(|t| Into::<char>::into(t.tile_type))))
-                .collect::<Vec<String>>()
-                .join("\n")
-        );
     }
-    print_grid(&grid);
+
+    fn to_char(&self) -> char {
+        self.tile_type.to_char()
+    }
+}
+
+fn parse(lines: &[String]) -> Result<Grid<Tile>> {
+    build_grid(lines)
+}
+
+fn part1(grid: &Grid<Tile>) -> Answer {
+    let mut grid = grid.clone();
+
+    debug!("{}", render_grid(&grid));
 
     struct QueueEntry {
         pos: Position,
@@ -137,49 +132,16 @@ pub fn part1() {
         }
     }
 
-    println!(
-        "{}",
-        grid.iter_rows()
-            .map(|row| String::from_iter(row.map(|t| match t.energized {
-                false => '.',
-                true => '#',
-            })))
-            .collect::<Vec<String>>()
-            .join("\n")
-    );
+    debug!("{}", format_grid(&grid, |t| t.energized.to_char()));
 
     let energized = grid.iter().filter(|t| t.energized).count();
-    println!("{}", energized);
+    energized.into()
 }
 
-pub fn part2() {
-    let lines = util::file_lines("inp16_2.txt");
-
-    let first_line = &lines[0];
-    let grid_vec: Vec<_> = lines
-        .iter()
-        .flat_map(|l| {
-            l.chars()
-                .map(|c| Tile {
-                    tile_type: c.into(),
-                    energized: false,
-                    energized_dir: [false; 4],
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect();
-    let mut grid = Grid::from_vec(grid_vec, first_line.len());
-
-    fn print_grid(grid: &Grid<Tile>) {
-        println!(
-            "{}",
-            grid.iter_rows()
-                .map(|row| String::from_iter(row.map(|t| Into::<char>::into(t.tile_type))))
-                .collect::<Vec<String>>()
-                .join("\n")
-        );
-    }
-    print_grid(&grid);
+fn part2(grid: &Grid<Tile>) -> Answer {
+    let mut grid = grid.clone();
+
+    debug!("{}", render_grid(&grid));
 
     struct QueueEntry {
         pos: Position,
@@ -307,5 +269,60 @@ pub fn part2() {
         .unwrap();
     let max_energy = max(max_col_energy, max_row_energy);
 
-    println!("{}", max_energy);
+    max_energy.into()
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Grid<Tile>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, grid_text, input, input_lines};
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in grid_text(".|-/\\")) {
+            prop_assert_eq!(render_grid(&parse(&lines).unwrap()), lines.join("\n"));
+        }
+    }
+
+    #[test]
+    fn grid_round_trip() {
+        let lines = input_lines(16, InputVariant::Example);
+        assert_eq!(render_grid(&parse(&lines).unwrap()), lines.join("\n"));
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&input::<Day16>(16, InputVariant::Example)), 46.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&input::<Day16>(16, InputVariant::Example)), 51.into());
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day16>(16);
+    }
 }
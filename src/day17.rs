@@ -1,6 +1,6 @@
-use crate::util::parse_digit;
-
-use super::util::{build_grid, file_lines, Direction, Position};
+use crate::error::Result;
+use crate::solution::{Answer, Solution};
+use crate::util::{build_grid_with, parse_digit, Direction, Position};
 use grid::Grid;
 
 use itertools::Itertools;
@@ -74,20 +74,11 @@ impl SearchState {
     }
 }
 
-pub fn part1() {
-    let lines = file_lines("inp17_2.txt");
-
-    let first_line = &lines[0];
-    let grid_vec: Vec<_> = lines
-        .iter()
-        .flat_map(|l| {
-            l.chars()
-                .map(|c| c.to_digit(10).unwrap() as u8)
-                .collect::<Vec<_>>()
-        })
-        .collect();
-    let grid = Grid::from_vec(grid_vec, first_line.len());
+fn parse(lines: &[String]) -> Result<Grid<u8>> {
+    build_grid_with(lines, parse_digit)
+}
 
+fn part1(grid: &Grid<u8>) -> Answer {
     let start_pos = Position { x: 0, y: 0 };
     let end_pos = Position {
         x: (grid.cols() - 1) as isize,
@@ -107,12 +98,12 @@ pub fn part1() {
                 // At the start: try both possible starting directions
                 vec![
                     successor_tuple(
-                        &s.advance_in_grid_with_dir(&grid, Direction::E).unwrap(),
-                        &grid,
+                        &s.advance_in_grid_with_dir(grid, Direction::E).unwrap(),
+                        grid,
                     ),
                     successor_tuple(
-                        &s.advance_in_grid_with_dir(&grid, Direction::S).unwrap(),
-                        &grid,
+                        &s.advance_in_grid_with_dir(grid, Direction::S).unwrap(),
+                        grid,
                     ),
                 ]
             }
@@ -120,8 +111,8 @@ pub fn part1() {
                 let mut succ = vec![];
 
                 let mut try_dir = |dir| {
-                    if let Some(next_state) = s.advance_in_grid_with_dir(&grid, dir) {
-                        succ.push(successor_tuple(&next_state, &grid));
+                    if let Some(next_state) = s.advance_in_grid_with_dir(grid, dir) {
+                        succ.push(successor_tuple(&next_state, grid));
                     }
                 };
 
@@ -140,14 +131,10 @@ pub fn part1() {
         |s| s.pos == end_pos,
     );
     let cost = result.unwrap().1;
-    println!("{}", cost);
+    cost.into()
 }
 
-pub fn part2() {
-    let lines = file_lines("inp17_2.txt");
-
-    let grid = build_grid(&lines, parse_digit);
-
+fn part2(grid: &Grid<u8>) -> Answer {
     let start_pos = Position { x: 0, y: 0 };
     let end_pos = Position {
         x: (grid.cols() - 1) as isize,
@@ -166,7 +153,7 @@ pub fn part2() {
                         // Never go back
                         return None;
                     }
-                    s.advance_in_grid_with_dir_2(&grid, new_dir)
+                    s.advance_in_grid_with_dir_2(grid, new_dir)
                 })
                 .collect_vec()
         },
@@ -174,5 +161,59 @@ pub fn part2() {
         |s| s.pos == end_pos,
     );
     let cost = result.unwrap().1;
-    println!("{}", cost);
+    cost.into()
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Grid<u8>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, grid_text, input};
+    use crate::util::format_grid;
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in grid_text("0123456789")) {
+            let text = format_grid(&parse(&lines).unwrap(), |&d| char::from(b'0' + d));
+            prop_assert_eq!(text, lines.join("\n"));
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(
+            part1(&input::<Day17>(17, InputVariant::Example)),
+            102.into()
+        );
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&input::<Day17>(17, InputVariant::Example)), 94.into());
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day17>(17);
+    }
 }
@@ -3,9 +3,15 @@ use std::{
     collections::VecDeque,
 };
 
-use super::util::{file_lines, print_grid, Direction, Position};
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::util::{
+    self, format_grid, Direction, DirectionScheme, GridTile, Neighbourhood, Position, SparseGrid,
+    Vector,
+};
 use grid::Grid;
 use itertools::Itertools;
+use log::{debug, trace};
 use std::fmt;
 
 #[derive(Debug, Default, PartialEq, Eq)]
@@ -13,72 +19,105 @@ struct Tile {
     dug: bool,
 }
 
-fn tile_print_dug(t: &Tile) -> char {
-    match t.dug {
-        true => '#',
-        false => '.',
+impl GridTile for Tile {
+    fn from_char(c: char) -> Result<Tile> {
+        bool::from_char(c).map(|dug| Tile { dug })
+    }
+
+    fn to_char(&self) -> char {
+        self.dug.to_char()
     }
 }
 
-pub fn part1() {
-    let lines = file_lines("inp18_2.txt");
-    const SIZE: usize = 700;
+pub struct DigInstruction {
+    dir: Direction,
+    dist: u32,
+    /// Color code including the parentheses, e.g. `(#70c710)`
+    color: String,
+}
 
-    let mut grid: Grid<Tile> = Grid::new(SIZE, SIZE);
+fn parse(lines: &[String]) -> Result<Vec<DigInstruction>> {
+    util::parse_lines(lines, |instruction| {
+        let [dir, dist, color] = instruction.split_whitespace().collect_vec()[..] else {
+            return Err(Error::expected("'<direction> <distance> (#<color>)'"));
+        };
+        let dir = dir
+            .parse()
+            .ok()
+            .and_then(|c| Direction::from_char(c, DirectionScheme::UpDown).ok())
+            .ok_or_else(|| Error::expected_at(instruction, dir, "U, R, D or L"))?;
+        let dist = dist
+            .parse()
+            .map_err(|_| Error::expected_at(instruction, dist, "a distance"))?;
+        // Part 2 reads a hex distance and a direction digit from the color code
+        let valid_color = color.len() == 9
+            && color.starts_with("(#")
+            && color.ends_with(')')
+            && color
+                .get(2..7)
+                .is_some_and(|hex| hex.chars().all(|c| c.is_ascii_hexdigit()))
+            && Direction::from_char(color.as_bytes()[7] as char, DirectionScheme::Digit).is_ok();
+        if !valid_color {
+            return Err(Error::expected_at(
+                instruction,
+                color,
+                "a color like (#70c710)",
+            ));
+        }
+        Ok(DigInstruction {
+            dir,
+            dist,
+            color: color.to_string(),
+        })
+    })
+}
 
-    let start_pos = Position {
-        x: (SIZE / 2) as isize,
-        y: (SIZE / 2) as isize,
-    };
+fn part1(instructions: &[DigInstruction]) -> Answer {
+    let mut grid: SparseGrid<Tile> = SparseGrid::new();
     {
-        let mut pos = start_pos;
-        grid[pos.as_grid_pos()].dug = true;
-
-        for instruction in lines {
-            let instr_parts = instruction.split_whitespace().collect_vec();
-            let dir = match instr_parts[0] {
-                "U" => Direction::N,
-                "R" => Direction::E,
-                "D" => Direction::S,
-                "L" => Direction::W,
-                _ => panic!(),
-            };
-            let dist: u32 = instr_parts[1].parse().unwrap();
-
-            for _ in 0..dist {
-                pos = pos.advance_in_grid(dir, &grid).unwrap();
-                grid[pos.as_grid_pos()].dug = true;
+        let mut pos = Position::ORIGIN;
+        grid.insert(pos, Tile { dug: true });
+
+        for instruction in instructions {
+            for _ in 0..instruction.dist {
+                pos += Vector::from(instruction.dir);
+                grid.insert(pos, Tile { dug: true });
             }
         }
     }
 
-    print_grid(&grid, tile_print_dug);
-
-    // Flood fill
-    {
-        let mut q = VecDeque::from([Position {
-            // FIXME This is specific to the input
-            x: start_pos.x - 1,
-            y: start_pos.y - 1,
-        }]);
-        while let Some(pos) = q.pop_back() {
-            grid[pos.as_grid_pos()].dug = true;
-            for dir in Direction::all() {
-                let new_pos = pos.advance_in_grid(dir, &grid).unwrap();
-                if !grid[new_pos.as_grid_pos()].dug {
-                    q.push_back(new_pos);
-                }
+    debug!(
+        "\n{}",
+        grid.format(|t| t.unwrap_or(&Tile::default()).to_char())
+    );
+
+    // Flood fill the outside of the trench, from the corners of a margin around it
+    let (top_left, bottom_right) = grid.bounds().unwrap();
+    let (top_left, bottom_right) = (
+        top_left - Vector { x: 1, y: 1 },
+        bottom_right + Vector { x: 1, y: 1 },
+    );
+    let extent = bottom_right - top_left;
+    let area = (extent.x + 1) as usize * (extent.y + 1) as usize;
+    // Marking both corners makes the bounds of the grid the area to fill
+    grid.insert(bottom_right, Tile { dug: false });
+    grid.insert(top_left, Tile { dug: false });
+    let mut outside = 2;
+    let mut q = VecDeque::from([top_left, bottom_right]);
+    while let Some(pos) = q.pop_back() {
+        for new_pos in pos.neighbours(Neighbourhood::VonNeumann) {
+            if grid.in_bounds(new_pos) && !grid.contains(new_pos) {
+                grid.insert(new_pos, Tile { dug: false });
+                outside += 1;
+                q.push_back(new_pos);
             }
         }
     }
 
-    let sum = grid.iter().filter(|t| t.dug).count();
-    println!("{}", sum);
+    (area - outside).into()
 }
 
-pub fn part2() {
-    let lines = file_lines("inp18_2.txt");
-
+fn part2(instructions: &[DigInstruction]) -> Answer {
     #[derive(Eq, PartialEq, Clone, Copy)]
     struct LineSeg {
         a: Position,
@@ -96,20 +135,14 @@ pub fn part2() {
             if self.dir.is_horizontal() {
                 assert_eq!(self.a.y, self.b.y);
                 if p.x >= min(self.a.x, self.b.x) && p.x <= max(self.a.x, self.b.x) {
-                    Some(Position {
-                        x: p.x,
-                        y: self.a.y,
-                    })
+                    Some(Position { y: self.a.y, ..p })
                 } else {
                     None
                 }
             } else {
                 assert_eq!(self.a.x, self.b.x);
                 if p.y >= min(self.a.y, self.b.y) && p.y <= max(self.a.y, self.b.y) {
-                    Some(Position {
-                        x: self.a.x,
-                        y: p.y,
-                    })
+                    Some(Position { x: self.a.x, ..p })
                 } else {
                     None
                 }
@@ -159,22 +192,18 @@ pub fn part2() {
     type Polygon = Vec<LineSeg>;
 
     let mut poly = Polygon::new();
-    let start_pos = Position { x: 0, y: 0 };
+    let start_pos = Position::ORIGIN;
 
     // Parse polygon
     {
         let mut pos = start_pos;
 
-        for instruction in lines {
-            let instr_parts = instruction.split_whitespace().collect_vec();
-            let dir = match instr_parts[2].chars().nth(7).unwrap() {
-                '0' => Direction::E,
-                '1' => Direction::S,
-                '2' => Direction::W,
-                '3' => Direction::N,
-                _ => panic!(),
-            };
-            let dist = isize::from_str_radix(&instr_parts[2][2..7], 16).unwrap();
+        for instruction in instructions {
+            // The real instructions are hidden in the color code
+            let color = &instruction.color;
+            let dir = Direction::from_char(color.chars().nth(7).unwrap(), DirectionScheme::Digit)
+                .unwrap();
+            let dist = isize::from_str_radix(&color[2..7], 16).unwrap();
 
             let next_pos = pos.advance_in_dir_by(dir, dist);
             poly.push(LineSeg {
@@ -186,22 +215,22 @@ pub fn part2() {
         }
     }
 
+    // Only fits the example, call it below to debug the simplification
+    #[allow(dead_code)]
     fn print_poly(poly: &Polygon) {
         const SIZE: usize = 20;
-        const OFFSET: usize = 2;
+        const OFFSET: isize = 2;
 
         let mut grid: Grid<char> = Grid::init(SIZE, SIZE, '.');
         for seg in poly {
-            let mut pos = seg.a;
-            pos.x += OFFSET as isize;
-            pos.y += OFFSET as isize;
+            let mut pos = seg.a + Vector::from((OFFSET, OFFSET));
             for _ in 0..seg.length() {
                 grid[pos.as_grid_pos()] = seg.dir.repr();
                 pos = pos.advance_in_grid(seg.dir, &grid).unwrap();
             }
         }
 
-        print_grid(&grid, |&c| c);
+        debug!("\n{}", format_grid(&grid, |&c| c));
     }
     //print_poly(&poly);
 
@@ -209,13 +238,11 @@ pub fn part2() {
 
     // Simplify polygon
     {
-        type Dir = Direction;
-
         let mut swap_in_out = false;
 
         while poly.len() > 4 {
             //println!("start!");
-            println!("{:?}", poly);
+            trace!("{:?}", poly);
             fn simplify(poly: &mut Polygon, swap_in_out: bool) -> Option<i64> {
                 for (index, (&l1, &l2, &l3, &l4)) in
                     poly.iter().circular_tuple_windows().enumerate()
@@ -229,9 +256,13 @@ pub fn part2() {
                             // We can simplify this segment
                             // Replace 3 lines by 1 or 2 lines
 
-                            println!(
+                            trace!(
                                 "Simplify in {}: {:?}/{:?}/{:?}  (next: {:?})",
-                                index, l1, l2, l3, l4
+                                index,
+                                l1,
+                                l2,
+                                l3,
+                                l4
                             );
 
                             let new_seg = LineSeg::new_from_pos(l1.a, new_end);
@@ -258,9 +289,12 @@ pub fn part2() {
                                 l1.length() * l2.length()
                             };
                             let area_diff = (area_before as i64) - (area_after as i64);
-                            println!(
+                            trace!(
                                 "<area> inside?{} before {} after {} -> diff {}",
-                                cutting_out_inside, area_before, area_after, area_diff
+                                cutting_out_inside,
+                                area_before,
+                                area_after,
+                                area_diff
                             );
 
                             // Replace this line
@@ -272,7 +306,7 @@ pub fn part2() {
 
                             if new_seg.b == l4.a {
                                 // Only one line needed
-                                println!("remove 2nd segment");
+                                trace!("remove 2nd segment");
                                 poly.remove((index + 1) % poly.len());
                                 poly.remove((index + 1) % poly.len());
                             } else {
@@ -280,7 +314,7 @@ pub fn part2() {
                                 let plen = poly.len();
                                 let l3mut = &mut poly[(index + 1) % plen];
                                 *l3mut = LineSeg::new_from_pos(new_seg.b, l4.a);
-                                println!("-----> replace seg after: {:?}", l3mut);
+                                trace!("-----> replace seg after: {:?}", l3mut);
                                 poly.remove((index + 2) % poly.len());
                             }
 
@@ -316,7 +350,7 @@ pub fn part2() {
                 for (index, (l0, l1)) in poly.iter().circular_tuple_windows().enumerate() {
                     assert_eq!(l0.b, l1.a);
                     if l0.dir == l1.dir {
-                        println!("merge {}! {:?},{:?}", index, l0, l1);
+                        trace!("merge {}! {:?},{:?}", index, l0, l1);
                         merged = true;
                         // Merge lines
                         poly[index].b = l1.b;
@@ -330,8 +364,76 @@ pub fn part2() {
         }
     }
 
-    println!("{:?}", poly);
+    trace!("{:?}", poly);
     let area = (poly[0].length() * poly[1].length()) as i64;
     let total_area = area + area_correct;
-    println!("{}", total_area);
+    total_area.into()
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Vec<DigInstruction>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, input};
+
+    fn render(instructions: &[DigInstruction]) -> Vec<String> {
+        instructions
+            .iter()
+            .map(|i| {
+                let dir = i.dir.display(DirectionScheme::UpDown);
+                format!("{} {} {}", dir, i.dist, i.color)
+            })
+            .collect()
+    }
+
+    fn dig_plan_text() -> impl Strategy<Value = Vec<String>> {
+        let instruction = ("[URDL]", 0..100_u32, "[0-9a-f]{5}[0-3]")
+            .prop_map(|(dir, dist, color)| format!("{} {} (#{})", dir, dist, color));
+        prop::collection::vec(instruction, 0..10)
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in dig_plan_text()) {
+            prop_assert_eq!(render(&parse(&lines).unwrap()), lines);
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&input::<Day18>(18, InputVariant::Example)), 62.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(
+            part2(&input::<Day18>(18, InputVariant::Example)),
+            952408144115_i64.into()
+        );
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day18>(18);
+    }
 }
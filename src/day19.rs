@@ -1,10 +1,12 @@
 use std::{
     cmp::{max, min},
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
 };
 
-use super::util::file_lines;
-use itertools::Itertools;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::util;
+use log::debug;
 use regex::Regex;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -44,7 +46,7 @@ impl Operator {
 }
 
 #[derive(Debug, PartialEq, Eq)]
-enum Verdict {
+pub enum Verdict {
     Reject,
     Accept,
     GoTo(String),
@@ -61,7 +63,7 @@ impl Verdict {
 }
 
 #[derive(Debug, PartialEq, Eq)]
-struct ComparisonInstruction {
+pub struct ComparisonInstruction {
     tag: ValueTag,
     op: Operator,
     value: usize,
@@ -69,13 +71,13 @@ struct ComparisonInstruction {
 }
 
 #[derive(Debug, PartialEq, Eq)]
-enum Instruction {
+pub enum Instruction {
     Compare(ComparisonInstruction),
     Judge(Verdict),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-struct PartGen<T> {
+pub struct PartGen<T> {
     x: T,
     m: T,
     a: T,
@@ -111,71 +113,159 @@ impl Part {
     }
 }
 
-fn parse(lines: &[String]) -> (HashMap<String, Vec<Instruction>>, Vec<Part>) {
-    let (instructions_s, parts_s) = lines.split_once(|l| l.is_empty()).unwrap();
+type Workflows = HashMap<String, Vec<Instruction>>;
+
+/// Jump that leads back to a workflow on the way from `label`, as line index and target
+///
+/// `visited` is `false` for the workflows on the way and `true` for those already known to
+/// end in a verdict.
+fn find_cycle<'a>(
+    label: &'a str,
+    jumps: &HashMap<&'a str, Vec<(usize, &'a str)>>,
+    visited: &mut HashMap<&'a str, bool>,
+) -> Option<(usize, &'a str)> {
+    if visited.contains_key(label) {
+        return None;
+    }
+    visited.insert(label, false);
+    for &(row, target) in jumps.get(label).into_iter().flatten() {
+        match visited.get(target) {
+            Some(false) => return Some((row, target)),
+            Some(true) => {}
+            None => {
+                if let Some(cycle) = find_cycle(target, jumps, visited) {
+                    return Some(cycle);
+                }
+            }
+        }
+    }
+    visited.insert(label, true);
+    None
+}
 
-    let re = Regex::new(r"^([xmas])([><])(\d+):(\w+)$").unwrap();
+/// Rating in the range that part 2 counts combinations of
+fn parse_rating(line: &str, value: &str) -> Result<usize> {
+    match value.parse() {
+        Ok(rating @ 1..=4000) => Ok(rating),
+        _ => Err(Error::expected_at(line, value, "a rating from 1 to 4000")),
+    }
+}
 
-    let workflows: HashMap<String, Vec<Instruction>> = instructions_s
-        .iter()
-        .map(|l| {
-            let (label, rem) = l.split_once('{').unwrap();
-            let rem = &rem[0..rem.len() - 1];
+fn parse_workflow(re: &Regex, l: &str) -> Result<(String, Vec<Instruction>)> {
+    let (label, rem) = l
+        .split_once('{')
+        .ok_or_else(|| Error::expected("'<label>{<rules>}'"))?;
+    let rem = rem
+        .strip_suffix('}')
+        .ok_or_else(|| Error::expected("'}'").at_column(l.chars().count()))?;
 
-            let instrs_s = rem.split(',').collect_vec();
-            let instrs = instrs_s
-                .iter()
-                .map(|&i| {
-                    let full_match = re.captures(i);
-                    match full_match {
-                        None => Instruction::Judge(Verdict::from_str(i)),
-                        Some(full_match) => Instruction::Compare(ComparisonInstruction {
-                            tag: ValueTag::from_char(
-                                full_match.get(1).unwrap().as_str().chars().next().unwrap(),
-                            ),
-                            op: Operator::from_char(
-                                full_match.get(2).unwrap().as_str().chars().next().unwrap(),
-                            ),
-                            value: full_match.get(3).unwrap().as_str().parse().unwrap(),
-                            verdict: Verdict::from_str(full_match.get(4).unwrap().as_str()),
-                        }),
+    let rules: Vec<&str> = rem.split(',').collect();
+    let instrs = rules
+        .iter()
+        .enumerate()
+        .map(|(index, &i)| {
+            let is_last = index == rules.len() - 1;
+            match re.captures(i) {
+                None if i.chars().all(|c| c.is_ascii_alphabetic()) && !i.is_empty() => {
+                    match is_last {
+                        true => Ok(Instruction::Judge(Verdict::from_str(i))),
+                        false => Err(Error::expected_at(l, i, "a rule like 'a<2006:qkq'")),
                     }
-                })
-                .collect_vec();
-
-            (label.to_string(), instrs)
+                }
+                None => Err(Error::expected_at(l, i, "a rule like 'a<2006:qkq'")),
+                // Parts that match no condition need somewhere to go
+                Some(_) if is_last => {
+                    Err(Error::expected_at(l, i, "a final rule without condition"))
+                }
+                Some(full_match) => {
+                    let (_, [tag, op, value, verdict]) = full_match.extract();
+                    Ok(Instruction::Compare(ComparisonInstruction {
+                        tag: ValueTag::from_char(tag.chars().next().unwrap()),
+                        op: Operator::from_char(op.chars().next().unwrap()),
+                        value: parse_rating(l, value)?,
+                        verdict: Verdict::from_str(verdict),
+                    }))
+                }
+            }
         })
-        .collect();
+        .collect::<Result<_>>()?;
 
-    let parts_re = Regex::new(r"^\{x=(\d+),m=(\d+),a=(\d+),s=(\d+)\}$").unwrap();
-    let parts = parts_s
+    Ok((label.to_string(), instrs))
+}
+
+fn parse(lines: &[String]) -> Result<(Workflows, Vec<Part>)> {
+    let separator = lines
         .iter()
-        .map(|l| {
-            let c = parts_re
-                .captures(l)
-                .unwrap()
-                .iter()
-                .skip(1)
-                .map(|g| g.unwrap().as_str().parse().unwrap())
-                .collect_vec();
-            Part {
-                x: c[0],
-                m: c[1],
-                a: c[2],
-                s: c[3],
+        .position(|l| l.is_empty())
+        .ok_or_else(|| Error::expected("an empty line between workflows and parts"))?;
+    let (instructions_s, parts_s) = (&lines[..separator], &lines[separator + 1..]);
+
+    let re = Regex::new(r"^([xmas])([><])(\d+):(\w+)$").unwrap();
+
+    let parsed = util::parse_lines(instructions_s, |l| parse_workflow(&re, l))?;
+    let labels: HashSet<&str> = parsed.iter().map(|(label, _)| label.as_str()).collect();
+    if !labels.contains("in") {
+        return Err(Error::expected("a workflow named 'in'"));
+    }
+    // Every rule has to send parts to 'A', 'R' or a workflow that exists
+    let mut jumps: HashMap<&str, Vec<(usize, &str)>> = HashMap::new();
+    for (row, ((label, instrs), l)) in parsed.iter().zip(instructions_s).enumerate() {
+        let (_, rem) = l.split_once('{').unwrap();
+        let rules = rem[..rem.len() - 1].split(',');
+        for (rule, instr) in rules.zip(instrs) {
+            let verdict = match instr {
+                Instruction::Compare(c) => &c.verdict,
+                Instruction::Judge(v) => v,
+            };
+            if let Verdict::GoTo(target) = verdict {
+                let target_s = rule.rsplit(':').next().unwrap();
+                if !labels.contains(target.as_str()) {
+                    return Err(
+                        Error::expected_at(l, target_s, "a defined workflow, 'A' or 'R'")
+                            .in_line(row),
+                    );
+                }
+                jumps
+                    .entry(label.as_str())
+                    .or_default()
+                    .push((row, target_s));
             }
+        }
+    }
+    // and no part may be sent around in circles
+    let mut visited = HashMap::new();
+    for (label, _) in &parsed {
+        if let Some((row, target_s)) = find_cycle(label, &jumps, &mut visited) {
+            let l = &instructions_s[row];
+            return Err(
+                Error::expected_at(l, target_s, "a jump that does not loop back").in_line(row),
+            );
+        }
+    }
+    let workflows: Workflows = parsed.into_iter().collect();
+
+    let parts_re = Regex::new(r"^\{x=(\d+),m=(\d+),a=(\d+),s=(\d+)\}$").unwrap();
+    let parts = util::parse_lines(parts_s, |l| {
+        let (_, values) = parts_re
+            .captures(l)
+            .ok_or_else(|| Error::expected("'{x=<rating>,m=<rating>,a=<rating>,s=<rating>}'"))?
+            .extract();
+        let [x, m, a, s] = values.map(|v| parse_rating(l, v));
+        Ok(Part {
+            x: x?,
+            m: m?,
+            a: a?,
+            s: s?,
         })
-        .collect_vec();
+    })
+    .map_err(|e| e.offset_lines(instructions_s.len() + 1))?;
 
-    (workflows, parts)
+    Ok((workflows, parts))
 }
 
-pub fn part1() {
-    let lines = file_lines("inp19_2.txt");
-    let (workflows, parts) = parse(&lines);
-
-    println!("{:?}", workflows);
-    println!("{:?}", parts);
+fn part1((workflows, parts): &(Workflows, Vec<Part>)) -> Answer {
+    debug!("{:?}", workflows);
+    debug!("{:?}", parts);
 
     let accepted_parts = parts.iter().filter(|part| {
         let mut workflow_name = "in".to_string();
@@ -222,13 +312,10 @@ pub fn part1() {
     });
 
     let sum: usize = accepted_parts.map(|p| p.sum()).sum();
-    println!("{}", sum);
+    sum.into()
 }
 
-pub fn part2() {
-    let lines = file_lines("inp19_2.txt");
-    let (workflows, _) = parse(&lines);
-
+fn part2((workflows, _): &(Workflows, Vec<Part>)) -> Answer {
     // Start and end, inclusive
     type TagRange = (usize, usize);
 
@@ -346,5 +433,212 @@ pub fn part2() {
         .map(|pr| range_len(pr.x) * range_len(pr.m) * range_len(pr.a) * range_len(pr.s))
         .sum();
 
-    println!("{}", sum);
+    sum.into()
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = (Workflows, Vec<Part>);
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, input};
+
+    fn render_verdict(verdict: &Verdict) -> &str {
+        match verdict {
+            Verdict::Accept => "A",
+            Verdict::Reject => "R",
+            Verdict::GoTo(label) => label,
+        }
+    }
+
+    /// Workflows sorted by label, then the parts
+    fn render((workflows, parts): &(Workflows, Vec<Part>)) -> Vec<String> {
+        let mut lines: Vec<String> = workflows
+            .iter()
+            .sorted_by_key(|(label, _)| *label)
+            .map(|(label, instrs)| {
+                let rules = instrs.iter().map(|instr| match instr {
+                    Instruction::Compare(c) => format!(
+                        "{}{}{}:{}",
+                        ["x", "m", "a", "s"][c.tag as usize],
+                        if c.op == Operator::Lt { '<' } else { '>' },
+                        c.value,
+                        render_verdict(&c.verdict)
+                    ),
+                    Instruction::Judge(verdict) => render_verdict(verdict).to_string(),
+                });
+                format!("{}{{{}}}", label, rules.format(","))
+            })
+            .collect();
+        lines.push(String::new());
+        lines.extend(
+            parts
+                .iter()
+                .map(|p| format!("{{x={},m={},a={},s={}}}", p.x, p.m, p.a, p.s)),
+        );
+        lines
+    }
+
+    /// `A`, `R` or one of the workflows in `targets`
+    fn verdict(targets: Vec<String>) -> BoxedStrategy<String> {
+        let accept_reject = prop_oneof![Just("A".to_string()), Just("R".to_string())];
+        match targets.is_empty() {
+            true => accept_reject.boxed(),
+            false => prop_oneof![accept_reject, prop::sample::select(targets)].boxed(),
+        }
+    }
+
+    /// Conditions, then the rule without condition that every workflow ends with
+    fn workflow_rules(targets: Vec<String>) -> impl Strategy<Value = String> {
+        let compare = ("[xmas]", "[<>]", 1..=4000_usize, verdict(targets.clone()))
+            .prop_map(|(tag, op, value, verdict)| format!("{}{}{}:{}", tag, op, value, verdict));
+        (prop::collection::vec(compare, 0..4), verdict(targets)).prop_map(|(mut rules, last)| {
+            rules.push(last);
+            rules.join(",")
+        })
+    }
+
+    /// Workflows including `in` that only jump to workflows after them, sorted by label like
+    /// [`render`] does, so that every part ends up accepted or rejected
+    fn system_text() -> impl Strategy<Value = Vec<String>> {
+        let workflows =
+            prop::collection::btree_set("[a-z]{1,3}", 0..8).prop_flat_map(|mut labels| {
+                labels.insert("in".to_string());
+                let labels: Vec<String> = labels.into_iter().collect();
+                let rules: Vec<_> = (0..labels.len())
+                    .map(|i| workflow_rules(labels[i + 1..].to_vec()))
+                    .collect();
+                (Just(labels), rules)
+            });
+        let rating = || 1..=4000_usize;
+        let part = [rating(), rating(), rating(), rating()];
+        (workflows, prop::collection::vec(part, 0..5)).prop_map(|((labels, rules), parts)| {
+            let mut lines: Vec<String> = labels
+                .iter()
+                .zip(rules)
+                .map(|(label, rules)| format!("{}{{{}}}", label, rules))
+                .collect();
+            lines.push(String::new());
+            lines.extend(
+                parts
+                    .iter()
+                    .map(|[x, m, a, s]| format!("{{x={},m={},a={},s={}}}", x, m, a, s)),
+            );
+            lines
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in system_text()) {
+            prop_assert_eq!(render(&parse(&lines).unwrap()), lines);
+        }
+
+        #[test]
+        fn solves_valid_systems(lines in system_text()) {
+            let system = parse(&lines).unwrap();
+            part1(&system);
+            part2(&system);
+        }
+    }
+
+    #[test]
+    fn rejects_undefined_workflow() {
+        let lines = ["in{a<5:px,R}", "px{a>2:zzz,A}", "", "{x=1,m=2,a=3,s=4}"].map(String::from);
+        let err = parse(&lines).unwrap_err().to_string();
+        assert_eq!(err, "<input>:2:8: expected a defined workflow, 'A' or 'R'");
+    }
+
+    #[test]
+    fn reports_char_columns() {
+        let lines = ["äö{R", "", "{x=1,m=2,a=3,s=4}"].map(String::from);
+        let err = parse(&lines).unwrap_err().to_string();
+        assert_eq!(err, "<input>:1:5: expected '}'");
+    }
+
+    #[test]
+    fn rejects_workflow_without_final_rule() {
+        let lines = ["in{x<5:A}", "", "{x=1,m=2,a=3,s=4}"].map(String::from);
+        let err = parse(&lines).unwrap_err().to_string();
+        assert_eq!(err, "<input>:1:4: expected a final rule without condition");
+        let lines = ["in{A,x<5:R}", "", "{x=1,m=2,a=3,s=4}"].map(String::from);
+        let err = parse(&lines).unwrap_err().to_string();
+        assert_eq!(err, "<input>:1:4: expected a rule like 'a<2006:qkq'");
+    }
+
+    #[test]
+    fn rejects_ratings_out_of_range() {
+        for (lines, expected) in [
+            (["in{x<0:A,R}", "", "{x=1,m=2,a=3,s=4}"], "<input>:1:6"),
+            (["in{x>4001:A,R}", "", "{x=1,m=2,a=3,s=4}"], "<input>:1:6"),
+            (["in{x<5:A,R}", "", "{x=1,m=2,a=0,s=4}"], "<input>:3:12"),
+            (
+                ["in{x<5:A,R}", "", "{x=1,m=99999999999999999999999,a=3,s=4}"],
+                "<input>:3:8",
+            ),
+        ] {
+            let err = parse(&lines.map(String::from)).unwrap_err().to_string();
+            assert_eq!(
+                err,
+                format!("{}: expected a rating from 1 to 4000", expected)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_jump_cycle() {
+        let lines = [
+            "in{x<5:px,R}",
+            "px{a>2:qq,A}",
+            "qq{px}",
+            "",
+            "{x=1,m=2,a=3,s=4}",
+        ];
+        let err = parse(&lines.map(String::from)).unwrap_err().to_string();
+        assert_eq!(err, "<input>:3:4: expected a jump that does not loop back");
+        let lines = ["in{in}", "", "{x=1,m=2,a=3,s=4}"].map(String::from);
+        let err = parse(&lines).unwrap_err().to_string();
+        assert_eq!(err, "<input>:1:4: expected a jump that does not loop back");
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(
+            part1(&input::<Day19>(19, InputVariant::Example)),
+            19114.into()
+        );
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(
+            part2(&input::<Day19>(19, InputVariant::Example)),
+            167409079868000_i64.into()
+        );
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day19>(19);
+    }
 }
@@ -0,0 +1,159 @@
+use std::cmp::max;
+
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::util;
+
+/// Game ID and the cube counts (red, green, blue) of every round
+type Game = (i64, Vec<[i64; 3]>);
+
+fn parse_round(line: &str, round: &str) -> Result<[i64; 3]> {
+    let mut round_contents = [0, 0, 0];
+    for e in round.split(',') {
+        let e = e.trim();
+        let (count_str, color) = e
+            .split_once(' ')
+            .ok_or_else(|| Error::expected_at(line, e, "'<count> <color>'"))?;
+        let count: i64 = count_str
+            .parse()
+            .map_err(|_| Error::expected_at(line, count_str, "a cube count"))?;
+        let index = match color {
+            "red" => 0,
+            "green" => 1,
+            "blue" => 2,
+            _ => return Err(Error::expected_at(line, color, "red, green or blue")),
+        };
+        round_contents[index] += count;
+    }
+    Ok(round_contents)
+}
+
+fn parse(lines: &[String]) -> Result<Vec<Game>> {
+    util::parse_lines(lines, |line| {
+        let (game_id_str, game_content_str) = line
+            .split_once(':')
+            .ok_or_else(|| Error::expected("'Game <id>: ...'"))?;
+        let game_id: i64 = game_id_str
+            .strip_prefix("Game ")
+            .and_then(|id| id.parse().ok())
+            .ok_or_else(|| Error::expected_at(line, game_id_str, "'Game <id>'"))?;
+
+        let contents = game_content_str
+            .split(';')
+            .map(|round| parse_round(line, round))
+            .collect::<Result<_>>()?;
+        Ok((game_id, contents))
+    })
+}
+
+fn part1(games: &[Game]) -> Answer {
+    let ok_games = games.iter().filter(|game| {
+        let limits = [12, 13, 14];
+        let possible = game
+            .1
+            .iter()
+            .all(|round| round[0] <= limits[0] && round[1] <= limits[1] && round[2] <= limits[2]);
+
+        possible
+    });
+
+    let sum_ok_games: i64 = ok_games.map(|g| g.0).sum();
+    sum_ok_games.into()
+}
+
+fn part2(games: &[Game]) -> Answer {
+    let power_per_game = games.iter().map(|game| {
+        let mut minimum_cubes = [0, 0, 0];
+        for round in &game.1 {
+            for i in 0..round.len() {
+                minimum_cubes[i] = max(minimum_cubes[i], round[i]);
+            }
+        }
+        let power: i64 = minimum_cubes.iter().product();
+        power
+    });
+    let sum_power: i64 = power_per_game.sum();
+    sum_power.into()
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Game>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, input};
+
+    const COLORS: [&str; 3] = ["red", "green", "blue"];
+
+    /// Cubes of every round in red, green, blue order, leaving out colors that were not shown
+    fn render(games: &[Game]) -> Vec<String> {
+        games
+            .iter()
+            .map(|(id, rounds)| {
+                let rounds = rounds.iter().map(|round| {
+                    round
+                        .iter()
+                        .zip(COLORS)
+                        .filter(|(&count, _)| count > 0)
+                        .map(|(count, color)| format!("{} {}", count, color))
+                        .join(", ")
+                });
+                format!("Game {}: {}", id, rounds.format("; "))
+            })
+            .collect()
+    }
+
+    fn games_text() -> impl Strategy<Value = Vec<String>> {
+        let round = prop::collection::btree_map(0..3_usize, 1..20_i64, 1..=3).prop_map(|cubes| {
+            cubes
+                .iter()
+                .map(|(&color, count)| format!("{} {}", count, COLORS[color]))
+                .join(", ")
+        });
+        let game = (1..1000_i64, prop::collection::vec(round, 1..6))
+            .prop_map(|(id, rounds)| format!("Game {}: {}", id, rounds.join("; ")));
+        prop::collection::vec(game, 0..8)
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in games_text()) {
+            prop_assert_eq!(render(&parse(&lines).unwrap()), lines);
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&input::<Day2>(2, InputVariant::Example)), 8.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&input::<Day2>(2, InputVariant::Example)), 2286.into());
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day2>(2);
+    }
+}
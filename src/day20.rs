@@ -1,13 +1,17 @@
 use std::collections::{BTreeMap, VecDeque};
 
-use super::util::file_lines;
 use itertools::Itertools;
+use log::{debug, log_enabled, Level};
+
+use crate::error::{Error, Result};
+use crate::repl::{step_count, CommandResult, Explore};
+use crate::solution::{Answer, Solution};
 
 trait ModBehaviorImpl {
     fn process_pulse(&mut self, origin: &str, p: bool) -> Option<bool>;
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct FlipFlopModule {
     state: bool,
 }
@@ -30,7 +34,7 @@ impl FlipFlopModule {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct BroadcasterModule {}
 
 impl BroadcasterModule {
@@ -45,7 +49,7 @@ impl ModBehaviorImpl for BroadcasterModule {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct ConjunctionModule {
     state: BTreeMap<String, bool>,
 }
@@ -70,22 +74,13 @@ impl ModBehaviorImpl for ConjunctionModule {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum ModuleBehavior {
     Broadcaster(BroadcasterModule),
     FlipFlop(FlipFlopModule),
     Conjunction(ConjunctionModule),
 }
 
-impl ModuleBehavior {
-    fn ff_state(&self) -> bool {
-        let ModuleBehavior::FlipFlop(fv) = self else {
-            panic!()
-        };
-        fv.state
-    }
-}
-
 impl ModBehaviorImpl for ModuleBehavior {
     fn process_pulse(&mut self, origin: &str, p: bool) -> Option<bool> {
         // FIXME not so nice
@@ -97,54 +92,41 @@ impl ModBehaviorImpl for ModuleBehavior {
     }
 }
 
-#[derive(Debug)]
-struct Module {
+#[derive(Debug, Clone)]
+pub struct Module {
     next_modules: Vec<String>,
     // FIXME dynamic dispatch? enum dispatch?
     behavior: ModuleBehavior,
 }
 
-use rand::seq::SliceRandom;
-
-pub fn part1() {
-    let lines = file_lines("inp20_2.txt");
+type ModuleMap = BTreeMap<String, Module>;
 
-    type ModuleMap = BTreeMap<String, Module>;
+fn parse(lines: &[String]) -> Result<ModuleMap> {
     let mut modules = ModuleMap::new();
-    for line in &lines {
-        let parts = line.split_whitespace().collect_vec();
-        let behavior = &parts[0][0..1];
-        let mut targets = parts[2..]
-            .iter()
-            .map(|s| s.strip_suffix(',').unwrap_or(s).to_string())
-            .collect_vec();
-        targets.shuffle(&mut rand::thread_rng());
-        match behavior {
-            "b" => modules.insert(
-                parts[0].to_string(),
-                Module {
-                    next_modules: targets,
-                    behavior: ModuleBehavior::Broadcaster(BroadcasterModule::new()),
-                },
-            ),
-            "%" => modules.insert(
-                parts[0][1..].to_string(),
-                Module {
-                    next_modules: targets,
-                    behavior: ModuleBehavior::FlipFlop(FlipFlopModule::new()),
-                },
-            ),
-            "&" => modules.insert(
-                parts[0][1..].to_string(),
-                Module {
-                    next_modules: targets,
-                    behavior: ModuleBehavior::Conjunction(ConjunctionModule::new()),
-                },
-            ),
-            _ => panic!(),
+    for (i, line) in lines.iter().enumerate() {
+        let (name, targets) = line
+            .split_once(" -> ")
+            .ok_or_else(|| Error::expected("'<module> -> <destinations>'").in_line(i))?;
+        let targets = targets.split(", ").map(|s| s.to_string()).collect_vec();
+        let (name, behavior) = if name == "broadcaster" {
+            (name, ModuleBehavior::Broadcaster(BroadcasterModule::new()))
+        } else if let Some(name) = name.strip_prefix('%') {
+            (name, ModuleBehavior::FlipFlop(FlipFlopModule::new()))
+        } else if let Some(name) = name.strip_prefix('&') {
+            (name, ModuleBehavior::Conjunction(ConjunctionModule::new()))
+        } else {
+            return Err(Error::expected("'broadcaster', '%<name>' or '&<name>'")
+                .in_line(i)
+                .at_column(0));
         };
+        modules.insert(
+            name.to_string(),
+            Module {
+                next_modules: targets,
+                behavior,
+            },
+        );
     }
-    println!("{:?}", modules);
 
     let module_names = modules.keys().cloned().collect_vec();
     for module_name in module_names {
@@ -157,136 +139,93 @@ pub fn part1() {
             if let ModuleBehavior::Conjunction(cm) =
                 &mut modules.get_mut(&next_module).unwrap().behavior
             {
-                println!("add_pre {} to {}", module_name, next_module);
                 cm.add_pre(&module_name);
             }
         }
     }
 
-    println!("{:?}", modules);
+    Ok(modules)
+}
 
-    let mut low_pulses = 0_u64;
-    let mut high_pulses = 0_u64;
-    struct QueueEntry {
-        pulse: bool,
-        origin_module: String,
-        module_name: String,
-    }
+struct QueueEntry {
+    pulse: bool,
+    origin_module: String,
+    module_name: String,
+}
 
-    for _ in 0..1000 {
-        let mut q = VecDeque::from([QueueEntry {
-            pulse: false,
-            origin_module: "".to_string(),
-            module_name: "broadcaster".to_string(),
-        }]);
-        low_pulses += 1;
-        while let Some(entry) = q.pop_back() {
-            let module = modules.get_mut(&entry.module_name);
-            if module.is_none() {
-                continue;
-            }
-            let module = module.unwrap();
-            let p = module
-                .behavior
-                .process_pulse(&entry.origin_module, entry.pulse);
-
-            if let Some(p) = p {
-                for next_module in &module.next_modules {
-                    q.push_back(QueueEntry {
-                        pulse: p,
-                        origin_module: entry.module_name.clone(),
-                        module_name: next_module.clone(),
-                    });
-                    if p {
-                        high_pulses += 1;
-                    } else {
-                        low_pulses += 1;
-                    }
-                }
+/// Press the button once and process all pulses, `on_pulse` sees each of them before it
+/// arrives at its module
+fn press_button(modules: &mut ModuleMap, mut on_pulse: impl FnMut(&ModuleMap, &QueueEntry)) {
+    let mut q = VecDeque::from([QueueEntry {
+        pulse: false,
+        origin_module: "".to_string(),
+        module_name: "broadcaster".to_string(),
+    }]);
+    while let Some(entry) = q.pop_front() {
+        on_pulse(modules, &entry);
+        let module = modules.get_mut(&entry.module_name);
+        if module.is_none() {
+            continue;
+        }
+        let module = module.unwrap();
+        let p = module
+            .behavior
+            .process_pulse(&entry.origin_module, entry.pulse);
+
+        if let Some(p) = p {
+            for next_module in &module.next_modules {
+                q.push_back(QueueEntry {
+                    pulse: p,
+                    origin_module: entry.module_name.clone(),
+                    module_name: next_module.clone(),
+                });
             }
         }
     }
+}
 
-    let pulse_mult = high_pulses * low_pulses;
-    println!("{}", pulse_mult);
+/// Numbers of low and high pulses sent by pressing the button `presses` times
+fn count_pulses(modules: &mut ModuleMap, presses: usize) -> (u64, u64) {
+    let mut low_pulses = 0_u64;
+    let mut high_pulses = 0_u64;
+    for _ in 0..presses {
+        press_button(modules, |_, entry| match entry.pulse {
+            true => high_pulses += 1,
+            false => low_pulses += 1,
+        });
+    }
+    (low_pulses, high_pulses)
 }
 
-pub fn part2() {
-    let lines = file_lines("inp20_2.txt");
+fn part1(modules: &ModuleMap) -> Answer {
+    let mut modules = modules.clone();
+    debug!("{:?}", modules);
 
-    type ModuleMap = BTreeMap<String, Module>;
-    let mut modules = ModuleMap::new();
-    for line in &lines {
-        let parts = line.split_whitespace().collect_vec();
-        let behavior = &parts[0][0..1];
-        let targets = parts[2..]
-            .iter()
-            .map(|s| s.strip_suffix(',').unwrap_or(s).to_string())
-            .collect_vec();
-        //targets.reverse();
-        match behavior {
-            "b" => modules.insert(
-                parts[0].to_string(),
-                Module {
-                    next_modules: targets,
-                    behavior: ModuleBehavior::Broadcaster(BroadcasterModule::new()),
-                },
-            ),
-            "%" => modules.insert(
-                parts[0][1..].to_string(),
-                Module {
-                    next_modules: targets,
-                    behavior: ModuleBehavior::FlipFlop(FlipFlopModule::new()),
-                },
-            ),
-            "&" => modules.insert(
-                parts[0][1..].to_string(),
-                Module {
-                    next_modules: targets,
-                    behavior: ModuleBehavior::Conjunction(ConjunctionModule::new()),
-                },
-            ),
-            _ => panic!(),
-        };
-    }
+    let (low_pulses, high_pulses) = count_pulses(&mut modules, 1000);
 
-    let module_names = modules.keys().cloned().collect_vec();
-    for module_name in module_names {
-        let next_modules = modules[&module_name].next_modules.clone();
-        for next_module in next_modules {
-            // FIXME this could be nicer
-            if !modules.contains_key(&next_module) {
-                continue;
-            }
-            if let ModuleBehavior::Conjunction(cm) =
-                &mut modules.get_mut(&next_module).unwrap().behavior
-            {
-                //println!("add_pre {} to {}", module_name, next_module);
-                cm.add_pre(&module_name);
-            }
-        }
-    }
+    let pulse_mult = high_pulses * low_pulses;
+    pulse_mult.into()
+}
 
-    // Output for graphviz to show the graph
-    println!("digraph {{");
+fn part2(modules: &ModuleMap) -> Answer {
+    let mut modules = modules.clone();
 
-    for module in &modules {
-        let color = match module.1.behavior {
-            ModuleBehavior::Broadcaster(_) => "red",
-            ModuleBehavior::Conjunction(_) => "blue",
-            ModuleBehavior::FlipFlop(_) => "green",
-        };
-        println!("{} [color = {}]", module.0, color);
-        for (i, next_module) in module.1.next_modules.iter().enumerate() {
-            println!("{} -> {} [label={}]", module.0, next_module, i);
+    // Output for graphviz to show the graph
+    if log_enabled!(Level::Debug) {
+        let mut dot = "digraph {\n".to_string();
+        for module in &modules {
+            let color = match module.1.behavior {
+                ModuleBehavior::Broadcaster(_) => "red",
+                ModuleBehavior::Conjunction(_) => "blue",
+                ModuleBehavior::FlipFlop(_) => "green",
+            };
+            dot += &format!("{} [color = {}]\n", module.0, color);
+            for (i, next_module) in module.1.next_modules.iter().enumerate() {
+                dot += &format!("{} -> {} [label={}]\n", module.0, next_module, i);
+            }
         }
-    }
-    println!("}}");
-
-    struct QueueEntry {
-        pulse: bool,
-        origin_module: String,
-        module_name: String,
+        dot += "}";
+        debug!("\n{}", dot);
     }
 
     let end_module = modules
@@ -300,13 +239,8 @@ pub fn part2() {
     };
     let mut done_in: Vec<Option<usize>> = vec![None; cbv.state.len()];
 
-    'outer: for i in 1.. {
-        let mut q = VecDeque::from([QueueEntry {
-            pulse: false,
-            origin_module: "".to_string(),
-            module_name: "broadcaster".to_string(),
-        }]);
-        while let Some(entry) = q.pop_front() {
+    for i in 1.. {
+        press_button(&mut modules, |modules, entry| {
             if entry.module_name == end_module {
                 let ModuleBehavior::Conjunction(cbv) = &modules[&entry.module_name].behavior else {
                     panic!()
@@ -316,35 +250,204 @@ pub fn part2() {
                         done_in[state_idx].get_or_insert(i);
                     }
                 }
-                if done_in.iter().all(|v| v.is_some()) {
-                    break 'outer;
-                }
-            }
-            let module = modules.get_mut(&entry.module_name);
-            if module.is_none() {
-                continue;
-            }
-            let module = module.unwrap();
-            let p = module
-                .behavior
-                .process_pulse(&entry.origin_module, entry.pulse);
-
-            if let Some(p) = p {
-                for next_module in &module.next_modules {
-                    q.push_back(QueueEntry {
-                        pulse: p,
-                        origin_module: entry.module_name.clone(),
-                        module_name: next_module.clone(),
-                    });
-                }
             }
+        });
+        if done_in.iter().all(|v| v.is_some()) {
+            break;
         }
     }
 
-    println!("{:?}", done_in);
+    debug!("{:?}", done_in);
 
     let lcm = done_in
         .iter()
         .fold(1_usize, |acc, n| num::Integer::lcm(&acc, &n.unwrap()));
-    println!("{}", lcm);
+    lcm.into()
+}
+
+/// Modules whose button is pressed step by step in the REPL
+struct Machine {
+    modules: ModuleMap,
+    presses: usize,
+    low_pulses: u64,
+    high_pulses: u64,
+}
+
+fn describe_module(name: &str, module: &Module) -> String {
+    let pulse = |high| if high { "high" } else { "low" };
+    let (prefix, state) = match &module.behavior {
+        ModuleBehavior::Broadcaster(_) => ("", String::new()),
+        ModuleBehavior::FlipFlop(ff) => ("%", if ff.state { " (on)" } else { " (off)" }.into()),
+        ModuleBehavior::Conjunction(cm) => {
+            let memory = cm
+                .state
+                .iter()
+                .map(|(input, &high)| format!("{} {}", input, pulse(high)))
+                .join(", ");
+            ("&", format!(" (last from {})", memory))
+        }
+    };
+    format!(
+        "{}{} -> {}{}",
+        prefix,
+        name,
+        module.next_modules.join(", "),
+        state
+    )
+}
+
+impl Explore for Machine {
+    fn commands(&self) -> &[(&'static str, &'static str)] {
+        &[
+            ("step [N]", "Press the button N times"),
+            ("show modules", "Show all modules with their state"),
+            ("show module <name>", "Show a module with its state"),
+        ]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Option<CommandResult> {
+        let result = match (command, args) {
+            ("step", args) => step_count(args).map(|count| {
+                let (low_pulses, high_pulses) = count_pulses(&mut self.modules, count);
+                self.presses += count;
+                self.low_pulses += low_pulses;
+                self.high_pulses += high_pulses;
+                format!(
+                    "{} presses, {} low and {} high pulses sent",
+                    self.presses, self.low_pulses, self.high_pulses
+                )
+            }),
+            ("show", ["modules"]) => Ok(self
+                .modules
+                .iter()
+                .map(|(name, module)| describe_module(name, module))
+                .join("\n")),
+            ("show", ["module", name]) => match self.modules.get(*name) {
+                Some(module) => Ok(describe_module(name, module)),
+                None => Err(format!("There is no module '{}'", name)),
+            },
+            _ => return None,
+        };
+        Some(result)
+    }
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = ModuleMap;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+
+    fn explore(input: &Self::Input) -> Option<Box<dyn Explore>> {
+        Some(Box::new(Machine {
+            modules: input.clone(),
+            presses: 0,
+            low_pulses: 0,
+            high_pulses: 0,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, input};
+
+    fn render(modules: &ModuleMap) -> Vec<String> {
+        modules
+            .iter()
+            .map(|(name, module)| {
+                let prefix = match module.behavior {
+                    ModuleBehavior::Broadcaster(_) => "",
+                    ModuleBehavior::FlipFlop(_) => "%",
+                    ModuleBehavior::Conjunction(_) => "&",
+                };
+                format!("{}{} -> {}", prefix, name, module.next_modules.join(", "))
+            })
+            .collect()
+    }
+
+    /// Module configurations sorted by name, like [`render`] does
+    fn configuration_text() -> impl Strategy<Value = Vec<String>> {
+        let targets = prop::collection::vec("[a-z]{1,3}", 1..4);
+        let module = ("[%&]", targets.clone());
+        let broadcaster = prop::option::of(targets);
+        (
+            prop::collection::btree_map("[a-z]{1,3}", module, 1..8),
+            broadcaster,
+        )
+            .prop_map(|(modules, broadcaster)| {
+                let mut lines: BTreeMap<String, String> = modules
+                    .into_iter()
+                    .map(|(name, (prefix, targets))| {
+                        let line = format!("{}{} -> {}", prefix, name, targets.join(", "));
+                        (name, line)
+                    })
+                    .collect();
+                if let Some(targets) = broadcaster {
+                    let line = format!("broadcaster -> {}", targets.join(", "));
+                    lines.insert("broadcaster".to_string(), line);
+                }
+                lines.into_values().collect()
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in configuration_text()) {
+            prop_assert_eq!(render(&parse(&lines).unwrap()), lines);
+        }
+    }
+
+    // The second example of part 1, there is no example for part 2
+    #[test]
+    fn part1_example() {
+        assert_eq!(
+            part1(&input::<Day20>(20, InputVariant::Example)),
+            11687500.into()
+        );
+    }
+
+    #[test]
+    fn explore_button_presses() {
+        let mut machine = Day20::explore(&input::<Day20>(20, InputVariant::Example)).unwrap();
+        let mut run = |command, args: &[&str]| machine.run(command, args).unwrap();
+        assert_eq!(
+            run("show", &["module", "a"]),
+            Ok("%a -> inv, con (off)".into())
+        );
+        assert_eq!(
+            run("step", &[]),
+            Ok("1 presses, 4 low and 4 high pulses sent".into())
+        );
+        assert_eq!(
+            run("show", &["module", "con"]),
+            Ok("&con -> output (last from a high, b high)".into())
+        );
+        assert_eq!(
+            run("step", &["999"]),
+            Ok("1000 presses, 4250 low and 2750 high pulses sent".into())
+        );
+        assert!(run("show", &["module", "output"]).is_err());
+        assert!(machine.run("press", &[]).is_none());
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day20>(20);
+    }
 }
@@ -1,8 +1,11 @@
 use std::collections::BTreeSet;
 
-use crate::util::{build_grid, file_lines, Direction, Position};
+use grid::Grid;
+use log::debug;
 
-use itertools::Itertools;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::util::{build_grid, Direction, GridTile, Neighbourhood, Position, TiledGrid};
 
 #[derive(Debug, PartialEq, Eq)]
 enum TileType {
@@ -12,45 +15,57 @@ enum TileType {
 }
 
 #[derive(Debug)]
-struct Tile {
+pub struct Tile {
     tile_type: TileType,
-    reached: bool,
 }
 
-fn parse_tile(c: char) -> Tile {
-    Tile {
-        tile_type: match c {
-            '.' => TileType::Garden,
-            '#' => TileType::Rock,
-            'S' => TileType::Start,
-            _ => panic!(),
-        },
-        reached: false,
+impl GridTile for Tile {
+    fn from_char(c: char) -> Result<Tile> {
+        Ok(Tile {
+            tile_type: match c {
+                '.' => TileType::Garden,
+                '#' => TileType::Rock,
+                'S' => TileType::Start,
+                _ => return Err(Error::expected("'.', '#' or 'S'")),
+            },
+        })
+    }
+
+    fn to_char(&self) -> char {
+        match self.tile_type {
+            TileType::Garden => '.',
+            TileType::Rock => '#',
+            TileType::Start => 'S',
+        }
     }
 }
 
-pub fn part1() {
-    let lines = file_lines("inp21_2.txt");
-    let grid = build_grid(&lines, parse_tile);
+fn find_start(grid: &Grid<Tile>) -> Position {
+    grid.indexed_iter()
+        .find(|(_, tile)| tile.tile_type == TileType::Start)
+        .unwrap()
+        .0
+        .into()
+}
 
-    struct QueueEntry {
-        pos: Position,
-        walk_len: usize,
+fn parse(lines: &[String]) -> Result<Grid<Tile>> {
+    let grid: Grid<Tile> = build_grid(lines)?;
+    if !grid.iter().any(|tile| tile.tile_type == TileType::Start) {
+        return Err(Error::expected("a start tile 'S'"));
     }
-    let start_pos = Position::from_grid_pos(
-        grid.indexed_iter()
-            .find(|(_, tile)| tile.tile_type == TileType::Start)
-            .unwrap()
-            .0,
-    );
+    Ok(grid)
+}
+
+/// Number of plots reachable in exactly `steps` steps from the start
+fn reachable(grid: &Grid<Tile>, steps: usize) -> usize {
+    let start_pos = find_start(grid);
 
     let mut plots: BTreeSet<Position> = vec![start_pos].into_iter().collect();
-    const WALK_MAX: usize = 64;
-    for _i in 0..WALK_MAX {
+    for _i in 0..steps {
         let mut next_plots = BTreeSet::<Position>::new();
         for pos in &plots {
             for dir in Direction::all() {
-                if let Some(next_pos) = pos.advance_in_grid(dir, &grid) {
+                if let Some(next_pos) = pos.advance_in_grid(dir, grid) {
                     if grid[next_pos.as_grid_pos()].tile_type != TileType::Rock {
                         next_plots.insert(next_pos);
                     }
@@ -60,150 +75,134 @@ pub fn part1() {
         plots = next_plots;
     }
 
-    let reached = plots.len();
-    println!("{}", reached);
+    plots.len()
 }
 
-pub fn part2() {
-    let lines = file_lines("inp21_1.txt");
-    let grid = build_grid(&lines, parse_tile);
-
-    struct QueueEntry {
-        pos: Position,
-        walk_len: usize,
-    }
-    let start_pos = Position::from_grid_pos(
-        grid.indexed_iter()
-            .find(|(_, tile)| tile.tile_type == TileType::Start)
-            .unwrap()
-            .0,
-    );
-    /*start_pos.x += 500 * grid.cols() as isize;
-    start_pos.y += 500 * grid.rows() as isize;*/
-
-    // Count possible steps for one fully filled grid unit
-    let mut plot_count_even = 0_u64;
-    let mut plot_count_odd = 0_u64;
-    {
-        // Start: odd step number (=1 step)
-        let mut plots = BTreeSet::from([start_pos]);
-        let mut done_plots = BTreeSet::new();
-
-        for i in 1_u64.. {
-            let mut next_plots = BTreeSet::new();
-            for pos in &plots {
-                for dir in Direction::all() {
-                    if let Some(next_pos) = pos.advance_in_grid(dir, &grid) {
-                        if grid[next_pos.as_grid_pos()].tile_type != TileType::Rock
-                            && !done_plots.contains(&next_pos)
-                        {
-                            next_plots.insert(next_pos);
-                            done_plots.insert(next_pos);
-                            if i % 2 == 0 {
-                                plot_count_even += 1;
-                            } else {
-                                plot_count_odd += 1;
-                            }
-                        }
-                    }
-                }
-            }
-            plots = next_plots;
-
-            if plots.is_empty() {
-                // Discovered all plots in the atomic garden unit
-                break;
-            }
-        }
-    }
+fn part1(grid: &Grid<Tile>) -> Answer {
+    const WALK_MAX: usize = 64;
+    reachable(grid, WALK_MAX).into()
+}
 
-    // Start: odd step number (=1 step)
+/// Number of plots reachable in exactly `steps` steps when the garden repeats infinitely
+fn reachable_in_repeating_grid(grid: &Grid<Tile>, start_pos: Position, steps: usize) -> usize {
+    let garden = TiledGrid::new(grid);
+    // A plot reached after i steps can be reached again after i + 2 steps by walking back
+    // and forth, so all plots with the same parity as the step count are reachable
     let mut plots = BTreeSet::from([start_pos]);
-    let mut done_plots = BTreeSet::<Position>::new();
-
-    const WALK_MAX: usize = 50;
+    let mut done_plots = BTreeSet::from([start_pos]);
+    let mut reachable = usize::from(steps.is_multiple_of(2));
 
-    let mut j = 0;
-    for i in 1..=WALK_MAX {
+    for i in 1..=steps {
         let mut next_plots = BTreeSet::<Position>::new();
         for pos in &plots {
-            for dir in Direction::all() {
-                let next_pos = pos.advance_in_dir_by(dir, 1);
-                if grid[next_pos.as_grid_pos_in_repeating_grid(&grid)].tile_type != TileType::Rock
-                    && !done_plots.contains(&next_pos)
-                {
+            for (next_pos, tile) in garden.neighbours(*pos, Neighbourhood::VonNeumann) {
+                if tile.tile_type != TileType::Rock && done_plots.insert(next_pos) {
                     next_plots.insert(next_pos);
-                    done_plots.insert(next_pos);
-                    if i % 2 == 0 {
-                        plot_count_even += 1;
-                    } else {
-                        plot_count_odd += 1;
+                    if i % 2 == steps % 2 {
+                        reachable += 1;
                     }
                 }
             }
         }
         plots = next_plots;
-        println!("{}", plots.len());
+    }
 
-        if plots.is_empty() {
-            j = i;
-            break;
-        }
+    reachable
+}
+
+fn part2(grid: &Grid<Tile>) -> Answer {
+    const WALK_MAX: usize = 26501365;
+
+    let start_pos = find_start(grid);
+    // The start row and column as well as the grid borders are free of rocks, so the reachable
+    // area grows by one garden unit in every direction every `size` steps. The number of reachable
+    // plots is then a quadratic function of the number of garden units walked.
+    let size = grid.rows();
+    let units = WALK_MAX / size;
+    let remainder = WALK_MAX % size;
+    let samples =
+        [0, 1, 2].map(|k| reachable_in_repeating_grid(grid, start_pos, remainder + k * size));
+    debug!("{:?}", samples);
+
+    // Newton forward differences
+    let d1 = samples[1] - samples[0];
+    let d2 = samples[2] + samples[0] - 2 * samples[1];
+    let reached = samples[0] + d1 * units + d2 * (units * (units - 1) / 2);
+    reached.into()
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Grid<Tile>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
     }
-    //println!("{:?}", done_plots);
-    let min_x = done_plots.iter().min_by(|&a, &b| a.x.cmp(&b.x)).unwrap().x - 10;
-    let max_x = done_plots.iter().max_by(|&a, &b| a.x.cmp(&b.x)).unwrap().x + 10;
-    let min_y = done_plots.iter().min_by(|&a, &b| a.y.cmp(&b.y)).unwrap().y - 4;
-    let max_y = done_plots.iter().max_by(|&a, &b| a.y.cmp(&b.y)).unwrap().y + 4;
-    //println!("{:?} / {:?}", min_pos, max_pos);
-    for y in min_y..=max_y {
-        println!(
-            "{}",
-            String::from_iter((min_x..=max_x).map(|x| {
-                let p = Position { x, y };
-                if grid[p.as_grid_pos_in_repeating_grid(&grid)].tile_type == TileType::Rock {
-                    '#'
-                } else if done_plots.contains(&p) {
-                    '+'
-                } else {
-                    '.'
-                }
-            }))
-        );
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
     }
 
-    return;
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
 
-    /*for i in 1.. {
-        let mut next_plots = BTreeSet::<Position>::new();
-        for pos in &plots {
-            for dir in Direction::all() {
-                if let Some(next_pos) = pos.advance_in_grid(dir, &grid) {
-                    if grid[next_pos.as_grid_pos()].tile_type != TileType::Rock
-                        && !done_plots.contains(&next_pos)
-                    {
-                        next_plots.insert(next_pos);
-                        done_plots.insert(next_pos);
-                        if i % 2 == 0 {
-                            plot_count_even += 1;
-                        } else {
-                            plot_count_odd += 1;
-                        }
-                    }
-                }
-            }
-        }
-        plots = next_plots;
-        println!("{}", plots.len());
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, grid_text, input, input_lines, set_tile};
+    use crate::util::render_grid;
+
+    /// Gardens with at least one start tile
+    fn garden_text() -> impl Strategy<Value = Vec<String>> {
+        (
+            grid_text(".#S"),
+            any::<prop::sample::Index>(),
+            any::<prop::sample::Index>(),
+        )
+            .prop_map(|(mut lines, row, col)| {
+                let row = row.index(lines.len());
+                set_tile(&mut lines, row, col, 'S');
+                lines
+            })
+    }
 
-        if plots.is_empty() {
-            j = i;
-            break;
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in garden_text()) {
+            prop_assert_eq!(render_grid(&parse(&lines).unwrap()), lines.join("\n"));
         }
-    }*/
+    }
+
+    #[test]
+    fn grid_round_trip() {
+        let lines = input_lines(21, InputVariant::Example);
+        assert_eq!(render_grid(&parse(&lines).unwrap()), lines.join("\n"));
+    }
+
+    #[test]
+    fn reachable_example() {
+        // The example walks 6 steps instead of 64
+        let grid = input::<Day21>(21, InputVariant::Example);
+        assert_eq!(reachable(&grid, 6), 16);
+    }
 
-    println!("{}", j);
+    #[test]
+    fn reachable_in_repeating_grid_example() {
+        let grid = input::<Day21>(21, InputVariant::Example);
+        let start_pos = find_start(&grid);
+        for (steps, plots) in [(6, 16), (10, 50), (50, 1594), (100, 6536)] {
+            assert_eq!(reachable_in_repeating_grid(&grid, start_pos, steps), plots);
+        }
+    }
 
-    let reached = plots.len();
-    println!("odd {} / even {}", plot_count_odd, plot_count_even)
+    #[test]
+    fn real() {
+        check_real::<Day21>(21);
+    }
 }
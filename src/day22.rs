@@ -1,38 +1,48 @@
-use crate::util::{file_lines, parse_ints};
-use indicatif::ParallelProgressIterator;
-use itertools::Itertools;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::util::{self, parse_ints_in};
+use indicatif::{ParallelProgressIterator, ProgressBar};
+use log::{log_enabled, trace, Level};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use std::{
     cmp::{max, min},
     collections::BTreeSet,
 };
 
-type Pos3D = (i64, i64, i64);
-type Brick = (Pos3D, Pos3D);
-
-fn parse_bricks(lines: &[String]) -> Vec<Brick> {
-    lines
-        .iter()
-        .map(|l| -> Brick {
-            let (b1, b2) = l.split_once('~').unwrap();
-            let i1 = parse_ints(b1);
-            let i2 = parse_ints(b2);
-            // Brick grows from .0 to .1
-            (
-                (min(i1[0], i2[0]), min(i1[1], i2[1]), min(i1[2], i2[2])),
-                (max(i1[0], i2[0]), max(i1[1], i2[1]), max(i1[2], i2[2])),
-            )
-        })
-        .collect_vec()
+pub type Pos3D = (i64, i64, i64);
+/// Opposite corners of a brick, the first one has the smaller coordinates
+pub type Brick = (Pos3D, Pos3D);
+
+fn parse_bricks(lines: &[String]) -> Result<Vec<Brick>> {
+    util::parse_lines(lines, |l| -> Result<Brick> {
+        let (b1, b2) = l
+            .split_once('~')
+            .ok_or_else(|| Error::expected("'<x>,<y>,<z>~<x>,<y>,<z>'"))?;
+        let coords = |s| match parse_ints_in::<i64>(l, s)?[..] {
+            [x, y, z] => Ok([x, y, z]),
+            _ => Err(Error::expected_at(l, s, "three coordinates")),
+        };
+        let i1 = coords(b1)?;
+        let i2 = coords(b2)?;
+        // Brick grows from .0 to .1
+        Ok((
+            (min(i1[0], i2[0]), min(i1[1], i2[1]), min(i1[2], i2[2])),
+            (max(i1[0], i2[0]), max(i1[1], i2[1]), max(i1[2], i2[2])),
+        ))
+    })
 }
 
 #[derive(PartialEq, Eq)]
-enum StopAfterFirstFall {
+pub enum StopAfterFirstFall {
     No,
     Yes,
 }
 
-fn perform_fall(
+/// Let `bricks` fall until they rest on the ground at z 1 or on other bricks
+///
+/// Returns the indices of the bricks that fell, or only the first of them with
+/// [`StopAfterFirstFall::Yes`].
+pub fn perform_fall(
     bricks: &mut Vec<Brick>,
     stop_after_first_fall: StopAfterFirstFall,
 ) -> BTreeSet<usize> {
@@ -86,20 +96,27 @@ fn perform_fall(
     fall_set
 }
 
-pub fn part1() {
-    let lines = file_lines("inp22_2.txt");
+/// Progress over all bricks, only shown when logging progress info
+fn progress_bar(len: usize) -> ProgressBar {
+    if log_enabled!(Level::Info) {
+        ProgressBar::new(len as u64)
+    } else {
+        ProgressBar::hidden()
+    }
+}
 
+fn part1(bricks: &[Brick]) -> Answer {
     // z: growing to sky
     // ground: z=0; bricks: z >= 1
 
-    let mut bricks = parse_bricks(&lines);
-    println!("{:?}", bricks);
+    let mut bricks = bricks.to_vec();
+    trace!("{:?}", bricks);
     perform_fall(&mut bricks, StopAfterFirstFall::No);
-    println!("{:?}", bricks);
+    trace!("{:?}", bricks);
 
     let can_disintegrate: usize = (0..bricks.len())
         .into_par_iter()
-        .progress()
+        .progress_with(progress_bar(bricks.len()))
         .filter(|&brick_i| {
             let mut new_bricks = bricks.clone();
             new_bricks.remove(brick_i);
@@ -109,19 +126,17 @@ pub fn part1() {
         })
         .count();
 
-    println!("{}", can_disintegrate);
+    can_disintegrate.into()
 }
 
-pub fn part2() {
-    let lines = file_lines("inp22_2.txt");
-
-    let mut bricks = parse_bricks(&lines);
+fn part2(bricks: &[Brick]) -> Answer {
+    let mut bricks = bricks.to_vec();
 
     perform_fall(&mut bricks, StopAfterFirstFall::No);
 
     let total_fall_count: usize = (0..bricks.len())
         .into_par_iter()
-        .progress()
+        .progress_with(progress_bar(bricks.len()))
         .map(|brick_i| {
             let mut new_bricks = bricks.clone();
             new_bricks.remove(brick_i);
@@ -131,5 +146,75 @@ pub fn part2() {
         })
         .sum();
 
-    println!("{}", total_fall_count);
+    total_fall_count.into()
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = Vec<Brick>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse_bricks(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real_slow, input};
+
+    fn render(bricks: &[Brick]) -> Vec<String> {
+        bricks
+            .iter()
+            .map(|((x1, y1, z1), (x2, y2, z2))| {
+                format!("{},{},{}~{},{},{}", x1, y1, z1, x2, y2, z2)
+            })
+            .collect()
+    }
+
+    /// Bricks with the smaller corner first, the way they are kept
+    fn snapshot_text() -> impl Strategy<Value = Vec<String>> {
+        let corner = (0..1000_i64, 0..1000_i64, 1..1000_i64);
+        let brick = (corner.clone(), corner).prop_map(|(a, b)| {
+            (
+                (min(a.0, b.0), min(a.1, b.1), min(a.2, b.2)),
+                (max(a.0, b.0), max(a.1, b.1), max(a.2, b.2)),
+            )
+        });
+        prop::collection::vec(brick, 0..20).prop_map(|bricks| render(&bricks))
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in snapshot_text()) {
+            prop_assert_eq!(render(&parse_bricks(&lines).unwrap()), lines);
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&input::<Day22>(22, InputVariant::Example)), 5.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&input::<Day22>(22, InputVariant::Example)), 7.into());
+    }
+
+    #[test]
+    fn real() {
+        check_real_slow::<Day22>(22);
+    }
 }
@@ -1,5 +1,4 @@
 // TODO remove unused code
-// TODO make part1 work again
 // TODO optimize part2 performance :-)
 
 use std::cmp::Ordering;
@@ -10,10 +9,13 @@ use std::fmt::Debug;
 use std::hash::BuildHasherDefault;
 use std::hash::Hash;
 
+use log::debug;
 use num::Zero;
 use rustc_hash::FxHasher;
 
-use super::util::{build_grid, file_lines, Direction, Position};
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::util::{build_grid, Direction, DirectionScheme, GridTile, Position};
 use grid::Grid;
 use indexmap::IndexMap;
 
@@ -22,7 +24,7 @@ use indexmap::map::Entry::{Occupied, Vacant};
 use itertools::Itertools;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum Tile {
+pub enum Tile {
     Path,
     Forest,
     SlopeTo(Direction),
@@ -35,6 +37,10 @@ struct SearchState {
     covered: BTreeSet<Position>,
 }
 
+/// Walk from `start_pos` in `start_dir` until the path branches or ends
+///
+/// Slopes can only be walked down, i.e. in their direction. Returns `None` if the first step
+/// is blocked.
 fn advance_to_junction(
     grid: &Grid<Tile>,
     start_pos: Position,
@@ -44,9 +50,14 @@ fn advance_to_junction(
     Direction, /* last direction that was walked in */
     usize,
 )> {
+    let can_enter = |pos: Position, dir: Direction| match grid[pos.as_grid_pos()] {
+        Tile::Forest => false,
+        Tile::Path => true,
+        Tile::SlopeTo(slope_dir) => slope_dir == dir,
+    };
     let mut dir = start_dir;
     let mut pos = start_pos.advance_in_grid(dir, grid)?;
-    if grid[pos.as_grid_pos()] == Tile::Forest {
+    if !can_enter(pos, dir) {
         return None;
     }
     let mut len = 1;
@@ -68,8 +79,13 @@ fn advance_to_junction(
             return Some((pos, dir, len));
         }
 
+        let (next_dir, next_pos) = next.unwrap();
+        if !can_enter(next_pos, next_dir) {
+            // Up a slope on the only way on, this is a dead end
+            return None;
+        }
         len += 1;
-        (dir, pos) = next.unwrap();
+        (dir, pos) = (next_dir, next_pos);
     }
 }
 
@@ -79,31 +95,20 @@ impl SearchState {
         grid: &Grid<Tile>,
         dir: Direction,
     ) -> Option<(SearchState, usize)> {
-        //let new_pos = self.pos.advance_in_grid(dir, grid)?;
-        let (new_pos, _, path_len) = advance_to_junction(grid, self.pos, dir)?;
-        //println!("{:?} {:?} -> {:?}", self.pos, dir, new_pos);
+        let (new_pos, last_dir, path_len) = advance_to_junction(grid, self.pos, dir)?;
         if self.covered.contains(&new_pos) {
             return None;
         }
-        /*let can_go = match grid[new_pos.as_grid_pos()] {
-            Tile::Forest => false,
-            Tile::Path => true,
-            Tile::SlopeTo(slope_dir) => slope_dir == dir,
-        };*/
         let mut new_covered = self.covered.clone();
         new_covered.insert(new_pos);
-        //if can_go {
         Some((
             SearchState {
                 pos: new_pos,
-                dir: Some(dir),
+                dir: Some(last_dir),
                 covered: new_covered,
             },
             path_len,
         ))
-        /* } else {
-            None
-        }*/
     }
 }
 
@@ -154,7 +159,7 @@ where
         index: 0,
     });
     let mut parents: FxIndexMap<N, (usize, C)> = FxIndexMap::default();
-    parents.insert(start.clone(), (usize::max_value(), Zero::zero()));
+    parents.insert(start.clone(), (usize::MAX, Zero::zero()));
     let mut target_reached = None;
     while let Some(SmallestHolder { cost, index }) = to_see.pop() {
         let successors = {
@@ -192,6 +197,12 @@ where
     (parents, target_reached)
 }
 
+/// Search from `start` over the edges given by `successors` in order of cost until `success`
+/// holds for a node
+///
+/// Returns every reached node with the index of its parent and its cost, and the index of the
+/// node that ended the search, if any. Unlike pathfinding's `dijkstra` this keeps the more
+/// expensive path when a node is reached again, which is what the longest hikes need.
 pub fn dijkstra<N, C, FN, IN, FS>(
     start: &N,
     mut successors: FN,
@@ -207,28 +218,45 @@ where
     run_dijkstra(start, &mut successors, &mut success)
 }
 
-pub fn part1() {
-    let lines = file_lines("inp23_2.txt");
-
-    let grid = build_grid(&lines, |c| match c {
-        '.' => Tile::Path,
-        '#' => Tile::Forest,
-        '^' => Tile::SlopeTo(Direction::N),
-        '>' => Tile::SlopeTo(Direction::E),
-        'v' => Tile::SlopeTo(Direction::S),
-        '<' => Tile::SlopeTo(Direction::W),
-        _ => panic!(),
-    });
+impl GridTile for Tile {
+    fn from_char(c: char) -> Result<Tile> {
+        match c {
+            '.' => Ok(Tile::Path),
+            '#' => Ok(Tile::Forest),
+            _ => Direction::from_char(c, DirectionScheme::AsciiArrow)
+                .map(Tile::SlopeTo)
+                .map_err(|_| Error::expected("'.', '#' or a slope (^>v<)")),
+        }
+    }
 
-    let start_pos = Position {
-        x: grid.iter_row(0).position(|&t| t == Tile::Path).unwrap() as isize,
-        y: 0,
-    };
+    fn to_char(&self) -> char {
+        match self {
+            Tile::Path => '.',
+            Tile::Forest => '#',
+            Tile::SlopeTo(dir) => dir.to_char(DirectionScheme::AsciiArrow),
+        }
+    }
+}
+
+fn parse(lines: &[String]) -> Result<Grid<Tile>> {
+    let grid: Grid<Tile> = build_grid(lines)?;
+    // The hike starts on the path in the top row and ends on the one in the bottom row
+    let last_row = grid.rows() - 1;
+    for (row, what) in [(0, "a start path tile"), (last_row, "an end path tile")] {
+        if !grid.iter_row(row).any(|&t| t == Tile::Path) {
+            return Err(Error::expected(what).in_line(row));
+        }
+    }
+    Ok(grid)
+}
+
+fn part1(grid: &Grid<Tile>) -> Answer {
+    let start_pos = Position::from((0, grid.iter_row(0).position(|&t| t == Tile::Path).unwrap()));
     let res = dijkstra(
         &SearchState {
             pos: start_pos,
             dir: None,
-            covered: BTreeSet::new(),
+            covered: BTreeSet::from([start_pos]),
         },
         |s| {
             Direction::all()
@@ -237,34 +265,35 @@ pub fn part1() {
                         // Never go back
                         return None;
                     }
-                    s.advance_in_grid_with_dir(&grid, new_dir)
+                    s.advance_in_grid_with_dir(grid, new_dir)
                 })
                 .collect_vec()
         },
-        |_| false, //|s| s.pos.y == (grid.rows() - 1) as isize,
+        |_| false,
     );
-    let end_state = res
+    // Every hike is a state of its own, so take the longest of those that reach the end
+    let cost = res
         .0
         .iter()
-        .find(|e| e.0.pos.y == (grid.rows() - 1) as isize)
+        .filter(|e| e.0.pos.y == (grid.rows() - 1) as isize)
+        .map(|e| e.1 .1)
+        .max()
         .unwrap();
-    let cost = end_state.1 .1;
-    println!("{}", cost);
+    cost.into()
 }
 
-pub fn part2() {
-    let lines = file_lines("inp23_2.txt");
-
-    let grid = build_grid(&lines, |c| match c {
-        '.' => Tile::Path,
-        '#' => Tile::Forest,
-        '^' | '>' | 'v' | '<' => Tile::Path,
-        _ => panic!(),
-    });
-    let start_pos = Position {
-        x: grid.iter_row(0).position(|&t| t == Tile::Path).unwrap() as isize,
-        y: 0,
-    };
+fn part2(grid: &Grid<Tile>) -> Answer {
+    // Slopes are just paths now
+    let grid = Grid::from_vec(
+        grid.iter()
+            .map(|&t| match t {
+                Tile::SlopeTo(_) => Tile::Path,
+                t => t,
+            })
+            .collect(),
+        grid.cols(),
+    );
+    let start_pos = Position::from((0, grid.iter_row(0).position(|&t| t == Tile::Path).unwrap()));
 
     struct NodeLink {
         destination: usize,
@@ -340,7 +369,7 @@ pub fn part2() {
         };
     }
 
-    println!("{:?}", nodes);
+    debug!("{:?}", nodes);
 
     let start_node = 0;
     let end_node = nodes
@@ -390,5 +419,95 @@ pub fn part2() {
         .map(|s| s.1 .1)
         .max()
         .unwrap();
-    println!("{}", cost);
+    cost.into()
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Grid<Tile>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real_slow, grid_text, input, input_lines, set_tile};
+    use crate::util::render_grid;
+
+    /// Trail maps with a path tile in the top and in the bottom row
+    fn trails_text() -> impl Strategy<Value = Vec<String>> {
+        (
+            grid_text(".#^>v<"),
+            any::<prop::sample::Index>(),
+            any::<prop::sample::Index>(),
+        )
+            .prop_map(|(mut lines, start, end)| {
+                let last_row = lines.len() - 1;
+                set_tile(&mut lines, 0, start, '.');
+                set_tile(&mut lines, last_row, end, '.');
+                lines
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in trails_text()) {
+            prop_assert_eq!(render_grid(&parse(&lines).unwrap()), lines.join("\n"));
+        }
+    }
+
+    #[test]
+    fn grid_round_trip() {
+        let lines = input_lines(23, InputVariant::Example);
+        assert_eq!(render_grid(&parse(&lines).unwrap()), lines.join("\n"));
+    }
+
+    #[test]
+    fn rejects_grid_without_start_or_end() {
+        let error = |s: &str| {
+            let lines: Vec<String> = s.lines().map(String::from).collect();
+            parse(&lines).unwrap_err().to_string()
+        };
+        assert_eq!(
+            error("###\n#.#\n#.#"),
+            "<input>:1: expected a start path tile"
+        );
+        assert_eq!(
+            error("#.#\n#.#\n#v#"),
+            "<input>:3: expected an end path tile"
+        );
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&input::<Day23>(23, InputVariant::Example)), 94.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(
+            part2(&input::<Day23>(23, InputVariant::Example)),
+            154.into()
+        );
+    }
+
+    #[test]
+    fn real() {
+        check_real_slow::<Day23>(23);
+    }
 }
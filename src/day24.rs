@@ -1,5 +1,8 @@
-use crate::util::{file_lines, parse_ints};
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::util::{self, parse_ints_in};
 use itertools::{izip, Itertools};
+use log::{debug, trace};
 use std::ops::RangeInclusive;
 use z3::ast::Ast;
 
@@ -10,13 +13,13 @@ struct Vec3D {
     z: f64,
 }
 
-impl From<Vec<f64>> for Vec3D {
-    fn from(v: Vec<f64>) -> Self {
-        assert_eq!(v.len(), 3);
-        Self {
-            x: v[0],
-            y: v[1],
-            z: v[2],
+impl TryFrom<Vec<f64>> for Vec3D {
+    type Error = Error;
+
+    fn try_from(v: Vec<f64>) -> Result<Self> {
+        match v[..] {
+            [x, y, z] => Ok(Self { x, y, z }),
+            _ => Err(Error::expected("three values")),
         }
     }
 }
@@ -28,32 +31,31 @@ impl Vec3D {
 }
 
 #[derive(Debug, PartialEq)]
-struct HailStone {
+pub struct HailStone {
     pos: Vec3D,
     velocity: Vec3D,
 }
 
-fn parse_stones(lines: &[String]) -> Vec<HailStone> {
-    lines
-        .iter()
-        .map(|l| {
-            let (p, v) = l.split_once('@').unwrap();
-            HailStone {
-                pos: parse_ints(p).into(),
-                velocity: parse_ints(v).into(),
-            }
+fn parse_stones(lines: &[String]) -> Result<Vec<HailStone>> {
+    util::parse_lines(lines, |l| {
+        let (p, v) = l
+            .split_once('@')
+            .ok_or_else(|| Error::expected("'<position> @ <velocity>'"))?;
+        let vec3d = |s| -> Result<Vec3D> {
+            parse_ints_in::<f64>(l, s)?
+                .try_into()
+                .map_err(|_: Error| Error::expected_at(l, s, "three values"))
+        };
+        Ok(HailStone {
+            pos: vec3d(p)?,
+            velocity: vec3d(v)?,
         })
-        .collect_vec()
+    })
 }
 
-pub fn part1() {
-    let lines = file_lines("inp24_2.txt");
-    let hailstones = parse_stones(&lines);
-
-    const MIN_COORD: f64 = 200000000000000.0;
-    const MAX_COORD: f64 = 400000000000000.0;
-
-    let intersections = hailstones
+/// Number of pairs of hailstones whose future paths cross within `area` in both x and y
+fn future_intersections(hailstones: &[HailStone], area: RangeInclusive<f64>) -> usize {
+    hailstones
         .iter()
         .tuple_combinations()
         .filter(|(a, b)| {
@@ -67,35 +69,39 @@ pub fn part1() {
             let px = x2 + m2 * v2x;
             let py = y2 + m2 * v2y;
 
-            println!(
+            trace!(
                 "{:?} intersect {:?} m1 {}/m2 {} at {},{}",
-                a, b, m1, m2, px, py
+                a,
+                b,
+                m1,
+                m2,
+                px,
+                py
             );
 
-            m1 >= 0.0
-                && m2 >= 0.0
-                && (MIN_COORD..=MAX_COORD).contains(&px)
-                && (MIN_COORD..=MAX_COORD).contains(&py)
+            m1 >= 0.0 && m2 >= 0.0 && area.contains(&px) && area.contains(&py)
         })
-        .count();
-
-    println!("{}", intersections);
+        .count()
 }
 
-pub fn part2() {
-    let lines = file_lines("inp24_2.txt");
-    let hailstones = parse_stones(&lines);
+fn part1(hailstones: &[HailStone]) -> Answer {
+    const MIN_COORD: f64 = 200000000000000.0;
+    const MAX_COORD: f64 = 400000000000000.0;
 
+    future_intersections(hailstones, MIN_COORD..=MAX_COORD).into()
+}
+
+fn part2(hailstones: &[HailStone]) -> Answer {
     // First try: Solve with sage -> see day24_p2.ipynb#
     // (3 hailstones are actually enough to solve the equations)
     (1..=3).for_each(|i| {
         let s = &hailstones[i];
-        println!("x{} = {}", i, s.pos.x);
-        println!("y{} = {}", i, s.pos.y);
-        println!("z{} = {}", i, s.pos.z);
-        println!("vx{} = {}", i, s.velocity.x);
-        println!("vy{} = {}", i, s.velocity.y);
-        println!("vz{} = {}", i, s.velocity.z);
+        debug!("x{} = {}", i, s.pos.x);
+        debug!("y{} = {}", i, s.pos.y);
+        debug!("z{} = {}", i, s.pos.z);
+        debug!("vx{} = {}", i, s.velocity.x);
+        debug!("vy{} = {}", i, s.velocity.y);
+        debug!("vz{} = {}", i, s.velocity.z);
     });
 
     // Now with Rust :-)
@@ -138,5 +144,83 @@ pub fn part2() {
         .iter()
         .map(|p| model.eval(p, true).unwrap().as_i64().unwrap())
         .sum();
-    println!("{}", pos_sum);
+    pos_sum.into()
+}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = Vec<HailStone>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse_stones(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, input};
+
+    fn render(hailstones: &[HailStone]) -> Vec<String> {
+        hailstones
+            .iter()
+            .map(|h| {
+                let vec3d = |v: &Vec3D| format!("{}, {}, {}", v.x, v.y, v.z);
+                format!("{} @ {}", vec3d(&h.pos), vec3d(&h.velocity))
+            })
+            .collect()
+    }
+
+    /// Hailstones with integer coordinates in the range of the real input
+    fn hailstones_text() -> impl Strategy<Value = Vec<String>> {
+        let position = || -(1_i64 << 50)..1_i64 << 50;
+        let velocity = || -1000..1000_i64;
+        let stone = (
+            [position(), position(), position()],
+            [velocity(), velocity(), velocity()],
+        )
+            .prop_map(|(p, v)| {
+                format!(
+                    "{}, {}, {} @ {}, {}, {}",
+                    p[0], p[1], p[2], v[0], v[1], v[2]
+                )
+            });
+        prop::collection::vec(stone, 0..10)
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in hailstones_text()) {
+            prop_assert_eq!(render(&parse_stones(&lines).unwrap()), lines);
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        // The example uses a much smaller test area than the real input
+        let hailstones = input::<Day24>(24, InputVariant::Example);
+        assert_eq!(future_intersections(&hailstones, 7.0..=27.0), 2);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&input::<Day24>(24, InputVariant::Example)), 47.into());
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day24>(24);
+    }
 }
@@ -1,5 +1,7 @@
-use crate::util::file_lines;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
 use itertools::Itertools;
+use log::{debug, log_enabled, trace, Level};
 use petgraph::{
     algo::connected_components, dot::Dot, graph::UnGraph, prelude::NodeIndex, visit::EdgeRef,
 };
@@ -30,8 +32,9 @@ fn graph_to_svg(graph: &UnGraph<String, u32>, filename: &str) {
     svgfile.write_all(&output.stdout).unwrap();
 }
 
-pub fn part1() {
-    let lines = file_lines("inp25_2.txt");
+type Graph = UnGraph<String, u32>;
+
+fn parse(lines: &[String]) -> Result<Graph> {
     let mut graph = UnGraph::<String, u32>::default();
     fn find_or_insert_node(graph: &mut UnGraph<String, u32>, name: &str) -> NodeIndex {
         match graph.node_indices().find(|i| graph[*i] == name) {
@@ -39,25 +42,32 @@ pub fn part1() {
             None => graph.add_node(name.to_string()),
         }
     }
-    for l in &lines {
-        let (name, e_strs) = l.split_once(": ").unwrap();
+    for (i, l) in lines.iter().enumerate() {
+        let (name, e_strs) = l
+            .split_once(": ")
+            .ok_or_else(|| Error::expected("'<component>: <components>'").in_line(i))?;
         let n = find_or_insert_node(&mut graph, name);
         for e in e_strs.split(' ') {
             let e_index = find_or_insert_node(&mut graph, e);
             graph.add_edge(n, e_index, 1);
         }
     }
+    Ok(graph)
+}
+
+fn part1(graph: &Graph) -> Answer {
+    let mut graph = graph.clone();
 
     // Sanity check that we parsed the graph correctly
     assert_eq!(1, connected_components(&graph));
     let node_count = graph.node_count();
 
-    if node_count < 100 {
+    if node_count < 100 && log_enabled!(Level::Debug) {
         graph_to_svg(&graph, "day25_graph.svg");
     }
 
     let min_cut = minimum_cut(&mut graph);
-    println!("Minimum cut: {:?}", min_cut);
+    debug!("Minimum cut: {:?}", min_cut);
     // All edges have weight 1 and the solution requires to cut exactly 3 edges
     // -> weight of the minimum cut has to be 3.
     assert_eq!(3, min_cut.1);
@@ -65,12 +75,8 @@ pub fn part1() {
     let nodes_a = min_cut.0.split(',');
     let node_count_a = nodes_a.count();
     let node_count_b = node_count - node_count_a;
-    println!(
-        "{} / {} -> {}",
-        node_count_a,
-        node_count_b,
-        node_count_a * node_count_b
-    );
+    debug!("{} / {}", node_count_a, node_count_b);
+    (node_count_a * node_count_b).into()
 }
 
 // STOER, M., & WAGNER, F. (1997). A Simple Min-Cut Algorithm. Journal of the ACM, 44(4), 585-591.
@@ -107,12 +113,16 @@ fn minimum_cut_phase(graph: &mut UnGraph<String, u32>, start_node: NodeIndex) ->
     cut_of_the_phase
 }
 
-fn minimum_cut(graph: &mut UnGraph<String, u32>) -> (String, u32) {
+/// Minimum cut of `graph` by Stoer-Wagner, as the merged node names of one side and the weight
+/// of the cut
+///
+/// Nodes are merged along the way, their names joined with `,`, so `graph` is consumed.
+pub fn minimum_cut(graph: &mut UnGraph<String, u32>) -> (String, u32) {
     // Choose first node as initial node "a"
     let start_node = graph.node_indices().next().unwrap();
     let mut min_cut: Option<(String, u32)> = None;
     while graph.node_count() > 1 {
-        println!("{}", graph.node_count());
+        trace!("{}", graph.node_count());
         let cut_of_the_phase = minimum_cut_phase(graph, start_node);
         min_cut = match min_cut {
             // First round
@@ -188,6 +198,84 @@ fn find_most_tightly_connected_node(
         .unwrap()
 }
 
-pub fn part2() {
+fn part2(_graph: &Graph) -> Answer {
     // trololo
+    "Merry Christmas!".into()
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = Graph;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real_slow, input};
+
+    /// One line per component with the connections it was listed with
+    fn render(graph: &Graph) -> Vec<String> {
+        graph
+            .edge_references()
+            .group_by(|e| e.source())
+            .into_iter()
+            .map(|(source, edges)| {
+                let targets = edges.map(|e| &graph[e.target()]).join(" ");
+                format!("{}: {}", graph[source], targets)
+            })
+            .collect()
+    }
+
+    fn wiring_text() -> impl Strategy<Value = Vec<String>> {
+        let name = || "[a-z]{3}";
+        let components =
+            prop::collection::btree_map(name(), prop::collection::vec(name(), 1..5), 0..8);
+        components.prop_map(|components| {
+            components
+                .iter()
+                .map(|(name, connected)| format!("{}: {}", name, connected.join(" ")))
+                .collect()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in wiring_text()) {
+            prop_assert_eq!(render(&parse(&lines).unwrap()), lines);
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&input::<Day25>(25, InputVariant::Example)), 54.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(
+            part2(&input::<Day25>(25, InputVariant::Example)),
+            "Merry Christmas!".into()
+        );
+    }
+
+    #[test]
+    fn real() {
+        check_real_slow::<Day25>(25);
+    }
 }
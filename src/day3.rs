@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use grid::Grid;
+use log::trace;
+use regex::Regex;
+
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::util::{self, Neighbourhood, Position};
+
+/// Engine schematic with the numbers found in it
+pub struct Schematic {
+    grid: Grid<u8>,
+    /// Part numbers with the row and the columns they span
+    numbers: Vec<(i64, usize, Range<usize>)>,
+}
+
+fn parse(lines: &[String]) -> Result<Schematic> {
+    let grid = util::build_grid_with(lines, |c| match c.is_ascii() {
+        true => Ok(c as u8),
+        false => Err(Error::expected("an ASCII schematic")),
+    })?;
+    let num_re = Regex::new(r"\d+").unwrap();
+    let numbers = util::parse_lines(lines, |line| {
+        num_re
+            .find_iter(line)
+            .map(|num_match| {
+                let part_no = num_match
+                    .as_str()
+                    .parse()
+                    .map_err(|_| Error::expected_at(line, num_match.as_str(), "a part number"))?;
+                Ok((part_no, num_match.range()))
+            })
+            .collect::<Result<Vec<_>>>()
+    })?;
+    let numbers = numbers
+        .into_iter()
+        .enumerate()
+        .flat_map(|(row, numbers)| {
+            numbers
+                .into_iter()
+                .map(move |(part_no, cols)| (part_no, row, cols))
+        })
+        .collect();
+    Ok(Schematic { grid, numbers })
+}
+
+/// Positions of the tiles touching a number, including diagonally, that match `is_symbol`
+fn adjacent_symbols(
+    grid: &Grid<u8>,
+    row: usize,
+    cols: Range<usize>,
+    is_symbol: fn(char) -> bool,
+) -> Vec<Position> {
+    let mut symbols: Vec<Position> = cols
+        .flat_map(|col| Position::from((row, col)).neighbours_in_grid(Neighbourhood::Moore, grid))
+        .filter(|pos| is_symbol(grid[pos.as_grid_pos()] as char))
+        .collect();
+    symbols.sort();
+    symbols.dedup();
+    symbols
+}
+
+fn part1(schematic: &Schematic) -> Answer {
+    fn is_symbol(c: char) -> bool {
+        c != '.' && !c.is_ascii_digit()
+    }
+
+    let grid = &schematic.grid;
+    let part_no_sum: i64 = schematic
+        .numbers
+        .iter()
+        .filter(|(_, row, cols)| !adjacent_symbols(grid, *row, cols.clone(), is_symbol).is_empty())
+        .map(|&(part_no, _, _)| {
+            trace!("{}", part_no);
+            part_no
+        })
+        .sum();
+    part_no_sum.into()
+}
+
+fn part2(schematic: &Schematic) -> Answer {
+    let mut gears: HashMap<Position, Vec<i64>> = HashMap::new();
+    for (part_no, row, cols) in &schematic.numbers {
+        for gear in adjacent_symbols(&schematic.grid, *row, cols.clone(), |c| c == '*') {
+            trace!("{} at gear {:?}", part_no, gear);
+            gears.entry(gear).or_default().push(*part_no);
+        }
+    }
+
+    let gear_ratio_sum: i64 = gears
+        .values()
+        .filter(|part_nos| part_nos.len() == 2)
+        .map(|part_nos| part_nos[0] * part_nos[1])
+        .sum();
+    gear_ratio_sum.into()
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Schematic;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, grid_text, input};
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in grid_text("0123456789.#$*+")) {
+            let schematic = parse(&lines).unwrap();
+            prop_assert_eq!(util::format_grid(&schematic.grid, |&c| c as char), lines.join("\n"));
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&input::<Day3>(3, InputVariant::Example)), 4361.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(
+            part2(&input::<Day3>(3, InputVariant::Example)),
+            467835.into()
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        // Too large for an i64
+        let lines = [".".repeat(24), format!("..{}", "9".repeat(22))];
+        let e = parse(&lines).err().unwrap();
+        assert_eq!(e.to_string(), "<input>:2:3: expected a part number");
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day3>(3);
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::HashSet;
+
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::util;
+
+pub struct Card {
+    winning_nos: HashSet<i64>,
+    card_nos: HashSet<i64>,
+}
+
+impl Card {
+    fn winning_count(&self) -> usize {
+        let winning_on_card = self.card_nos.intersection(&self.winning_nos);
+        winning_on_card.count()
+    }
+}
+
+fn parse(lines: &[String]) -> Result<Vec<Card>> {
+    util::parse_lines(lines, |line| {
+        let mut parts = line.splitn(3, [':', '|']);
+        let _ = parts.next();
+
+        let mut parse_nos = || -> Result<HashSet<i64>> {
+            parts
+                .next()
+                .ok_or_else(|| Error::expected("'Card <id>: <numbers> | <numbers>'"))?
+                .split_whitespace()
+                .map(|s| {
+                    s.parse::<i64>()
+                        .map_err(|_| Error::expected_at(line, s, "a number"))
+                })
+                .collect()
+        };
+
+        Ok(Card {
+            winning_nos: parse_nos()?,
+            card_nos: parse_nos()?,
+        })
+    })
+}
+
+fn part1(cards: &[Card]) -> Answer {
+    let sum: i64 = cards
+        .iter()
+        .map(|card| {
+            let winning_count = card.winning_count();
+
+            if winning_count == 0 {
+                0
+            } else {
+                2_i64.pow((winning_count as u32) - 1)
+            }
+        })
+        .sum();
+
+    sum.into()
+}
+
+fn part2(cards: &[Card]) -> Answer {
+    let mut card_counts: Vec<i64> = vec![1; cards.len()];
+
+    for (card_no, card) in cards.iter().enumerate() {
+        let winning_count = card.winning_count();
+
+        let this_card_count = card_counts[card_no];
+        for i in 0..winning_count {
+            card_counts[card_no + 1 + i] += this_card_count;
+        }
+    }
+
+    let sum: i64 = card_counts.iter().sum();
+
+    sum.into()
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<Card>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, input};
+
+    /// Cards numbered from 1 with their numbers sorted
+    fn render(cards: &[Card]) -> Vec<String> {
+        let numbers = |nos: &HashSet<i64>| nos.iter().sorted().join(" ");
+        cards
+            .iter()
+            .enumerate()
+            .map(|(i, card)| {
+                let (winning_nos, card_nos) = (numbers(&card.winning_nos), numbers(&card.card_nos));
+                format!("Card {}: {} | {}", i + 1, winning_nos, card_nos)
+            })
+            .collect()
+    }
+
+    fn cards_text() -> impl Strategy<Value = Vec<String>> {
+        let numbers = || prop::collection::btree_set(1..100_i64, 1..10);
+        prop::collection::vec((numbers(), numbers()), 0..8).prop_map(|cards| {
+            cards
+                .iter()
+                .enumerate()
+                .map(|(i, (winning_nos, card_nos))| {
+                    let (winning_nos, card_nos) =
+                        (winning_nos.iter().join(" "), card_nos.iter().join(" "));
+                    format!("Card {}: {} | {}", i + 1, winning_nos, card_nos)
+                })
+                .collect()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in cards_text()) {
+            prop_assert_eq!(render(&parse(&lines).unwrap()), lines);
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&input::<Day4>(4, InputVariant::Example)), 13.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&input::<Day4>(4, InputVariant::Example)), 30.into());
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day4>(4);
+    }
+}
@@ -0,0 +1,309 @@
+use std::cmp::min;
+use std::collections::BTreeMap;
+
+use log::trace;
+
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+
+#[derive(Copy, Clone, Debug)]
+struct Range {
+    start: i64,
+    length: i64,
+}
+
+impl Range {
+    fn move_forward(&mut self, amount: i64) {
+        self.start += amount;
+        self.length -= amount;
+        assert!(self.length >= 0);
+    }
+    fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+    fn end(&self) -> i64 {
+        self.start + self.length - 1
+    }
+    fn overlaps(&self, other: &Range) -> bool {
+        (self.start <= other.end()) && (other.start <= self.end())
+    }
+}
+
+pub struct ConvMap {
+    source_type: String,
+    dest_type: String,
+    rules_source_to_dest: BTreeMap<i64, Range>,
+}
+
+pub struct Almanac {
+    seeds: Vec<i64>,
+    maps: Vec<ConvMap>,
+}
+
+fn parse_numbers(line: &str, s: &str) -> Result<Vec<i64>> {
+    s.split_whitespace()
+        .map(|n| {
+            n.parse::<i64>()
+                .map_err(|_| Error::expected_at(line, n, "a number"))
+        })
+        .collect()
+}
+
+fn parse_line(maps: &mut Vec<ConvMap>, line: &str) -> Result<()> {
+    if let Some(map_name) = line.strip_suffix(" map:") {
+        let mut types = map_name.split("-to-");
+        let (Some(source_type), Some(dest_type), None) = (types.next(), types.next(), types.next())
+        else {
+            return Err(Error::expected("'<source>-to-<destination> map:'"));
+        };
+        maps.push(ConvMap {
+            source_type: source_type.to_string(),
+            dest_type: dest_type.to_string(),
+            rules_source_to_dest: BTreeMap::new(),
+        });
+        return Ok(());
+    }
+
+    let current_map = maps
+        .last_mut()
+        .ok_or_else(|| Error::expected("a map header"))?;
+    let [dest_start, src_start, range_len] = parse_numbers(line, line)?[..] else {
+        return Err(Error::expected("three numbers"));
+    };
+    current_map.rules_source_to_dest.insert(
+        src_start,
+        Range {
+            start: dest_start,
+            length: range_len,
+        },
+    );
+    Ok(())
+}
+
+fn parse(lines: &[String]) -> Result<Almanac> {
+    let mut maps: Vec<ConvMap> = Vec::new();
+
+    let seed_line = lines.first().map_or("", |l| l.as_str());
+    let seeds = match seed_line.strip_prefix("seeds:") {
+        Some(seeds) => parse_numbers(seed_line, seeds),
+        None => Err(Error::expected("'seeds: <numbers>'")),
+    }
+    .map_err(|e| e.in_line(0))?;
+
+    for (i, line) in lines.iter().enumerate().skip(1) {
+        if !line.is_empty() {
+            parse_line(&mut maps, line).map_err(|e| e.in_line(i))?;
+        }
+    }
+
+    Ok(Almanac { seeds, maps })
+}
+
+fn part1(almanac: &Almanac) -> Answer {
+    let maps = &almanac.maps;
+    let min_loc = almanac
+        .seeds
+        .iter()
+        .filter_map(|seed| {
+            let mut cur_type = "seed".to_string();
+            let mut cur_no = *seed;
+
+            loop {
+                let next_map = maps.iter().find(|m| m.source_type == cur_type).unwrap();
+
+                let rule = next_map
+                    .rules_source_to_dest
+                    .iter()
+                    .find(|r| cur_no >= *r.0 && cur_no < r.0 + r.1.length);
+
+                if let Some(rule_val) = rule {
+                    let no_in_range = cur_no - rule_val.0;
+                    cur_no = rule_val.1.start + no_in_range;
+                }
+
+                cur_type = next_map.dest_type.clone();
+
+                if cur_type == "location" {
+                    trace!("OK: seed {} -> location {}", seed, cur_no);
+                    return Some(cur_no);
+                }
+            }
+        })
+        .min()
+        .unwrap();
+
+    min_loc.into()
+}
+
+fn part2(almanac: &Almanac) -> Answer {
+    let maps = &almanac.maps;
+    let loc_ranges = almanac.seeds.chunks_exact(2).flat_map(|seed| {
+        let mut cur_type = "seed".to_string();
+        let mut cur_ranges = vec![Range {
+            start: seed[0],
+            length: seed[1],
+        }];
+
+        loop {
+            let next_map = maps.iter().find(|m| m.source_type == cur_type).unwrap();
+
+            let mut next_ranges: Vec<Range> = Vec::new();
+
+            for range in cur_ranges {
+                let mut remaining_range = range;
+
+                trace!(
+                    "{} -> {} for range {:?}",
+                    cur_type,
+                    next_map.dest_type,
+                    range
+                );
+
+                while !remaining_range.is_empty() {
+                    let next_rule = next_map
+                        .rules_source_to_dest
+                        .iter() // should be sorted by key?
+                        .find(|r| {
+                            remaining_range.overlaps(&Range {
+                                start: *r.0,
+                                length: r.1.length,
+                            })
+                        });
+
+                    match next_rule {
+                        None => {
+                            // Ran out of rules
+                            next_ranges.push(remaining_range);
+                            break;
+                        }
+                        Some(next_rule) => {
+                            let remainder_before = next_rule.0 - remaining_range.start;
+                            if remainder_before > 0 {
+                                // Unmapped space before next rule
+                                next_ranges.push(Range {
+                                    start: remaining_range.start,
+                                    length: remainder_before,
+                                });
+                                // Reduce scope
+                                remaining_range.move_forward(remainder_before);
+                            }
+                            // Mapped space
+                            let offset_to_rule = remaining_range.start - next_rule.0;
+                            assert!(offset_to_rule >= 0);
+                            let mapped_length =
+                                min(next_rule.1.length - offset_to_rule, remaining_range.length);
+                            next_ranges.push(Range {
+                                start: next_rule.1.start + offset_to_rule,
+                                length: mapped_length,
+                            });
+                            // Reduce scope
+                            remaining_range.move_forward(mapped_length);
+                        }
+                    }
+                }
+            }
+            trace!(" yielded ranges: {:?}", next_ranges);
+
+            cur_ranges = next_ranges;
+
+            cur_type = next_map.dest_type.clone();
+
+            if cur_type == "location" {
+                break;
+            }
+        }
+
+        cur_ranges
+    });
+
+    let min_loc = loc_ranges.map(|r| r.start).min().unwrap();
+
+    min_loc.into()
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Almanac;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, input};
+
+    fn render(almanac: &Almanac) -> Vec<String> {
+        let seeds: String = almanac.seeds.iter().map(|s| format!(" {}", s)).collect();
+        let mut lines = vec![format!("seeds:{}", seeds)];
+        for map in &almanac.maps {
+            lines.push(String::new());
+            lines.push(format!("{}-to-{} map:", map.source_type, map.dest_type));
+            for (src_start, range) in &map.rules_source_to_dest {
+                lines.push(format!("{} {} {}", range.start, src_start, range.length));
+            }
+        }
+        lines
+    }
+
+    /// Almanacs with the rules of each map sorted by source, the order they are kept in
+    fn almanac_text() -> impl Strategy<Value = Vec<String>> {
+        let seeds = prop::collection::vec(0..1_i64 << 40, 0..6);
+        let rules =
+            prop::collection::btree_map(0..1_i64 << 40, (0..1_i64 << 40, 1..1_i64 << 20), 0..5);
+        let map = ("[a-z]{1,11}", "[a-z]{1,11}", rules);
+        (seeds, prop::collection::vec(map, 0..8)).prop_map(|(seeds, maps)| {
+            let almanac = Almanac {
+                seeds,
+                maps: maps
+                    .into_iter()
+                    .map(|(source_type, dest_type, rules)| ConvMap {
+                        source_type,
+                        dest_type,
+                        rules_source_to_dest: rules
+                            .into_iter()
+                            .map(|(src, (start, length))| (src, Range { start, length }))
+                            .collect(),
+                    })
+                    .collect(),
+            };
+            render(&almanac)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in almanac_text()) {
+            prop_assert_eq!(render(&parse(&lines).unwrap()), lines);
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&input::<Day5>(5, InputVariant::Example)), 35.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&input::<Day5>(5, InputVariant::Example)), 46.into());
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day5>(5);
+    }
+}
@@ -0,0 +1,131 @@
+use itertools::Itertools;
+use log::debug;
+
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+
+/// Race time and best distance so far
+type Race = (i64, i64);
+
+fn parse_numbers(lines: &[String], index: usize, label: &str) -> Result<Vec<i64>> {
+    let line = lines.get(index).map_or("", |l| l.as_str());
+    let numbers = line
+        .strip_prefix(label)
+        .ok_or_else(|| Error::expected(format!("'{} <numbers>'", label)).in_line(index))?;
+    numbers
+        .split_whitespace()
+        .map(|t| {
+            t.parse::<i64>()
+                .map_err(|_| Error::expected_at(line, t, "a number").in_line(index))
+        })
+        .collect()
+}
+
+fn parse(lines: &[String]) -> Result<Vec<Race>> {
+    let times = parse_numbers(lines, 0, "Time:")?;
+    let distances = parse_numbers(lines, 1, "Distance:")?;
+    if times.len() != distances.len() {
+        return Err(Error::expected(format!("{} distances", times.len())).in_line(1));
+    }
+
+    Ok(times.into_iter().zip(distances).collect())
+}
+
+fn part1(races: &[Race]) -> Answer {
+    let ways_to_win_per_race = races.iter().map(|race| {
+        let time = race.0;
+        let best_distance = race.1;
+        let win_distances = (1..time)
+            .map(|charge_time| {
+                let travel_time = time - charge_time;
+                let speed = charge_time;
+
+                speed * travel_time
+            })
+            .filter(|d| *d > best_distance);
+        let no_win_distances = win_distances.count();
+        //println!("- {}", no_win_distances);
+        no_win_distances as i64
+    });
+
+    let solution: i64 = ways_to_win_per_race.product();
+    solution.into()
+}
+
+fn part2(races: &[Race]) -> Answer {
+    // The spaces between the numbers have to be ignored -> there is only one race
+    let join_digits = |nums: Vec<i64>| nums.iter().join("").parse::<i64>().unwrap();
+    let time = join_digits(races.iter().map(|r| r.0).collect());
+    let best_distance = join_digits(races.iter().map(|r| r.1).collect());
+
+    let det = ((time * time - 4 * best_distance) as f64).sqrt();
+    let min_charge_time = ((time as f64 - det) / 2.0).ceil() as i64;
+    let max_charge_time = ((time as f64 + det) / 2.0).floor() as i64;
+
+    let no_win_distances = max_charge_time - min_charge_time + 1;
+    debug!("- {}", no_win_distances);
+    no_win_distances.into()
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Vec<Race>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, input};
+
+    fn render(races: &[Race]) -> Vec<String> {
+        let times: String = races.iter().map(|(time, _)| format!(" {}", time)).collect();
+        let distances: String = races.iter().map(|(_, dist)| format!(" {}", dist)).collect();
+        vec![format!("Time:{}", times), format!("Distance:{}", distances)]
+    }
+
+    fn races_text() -> impl Strategy<Value = Vec<String>> {
+        prop::collection::vec((0..1_i64 << 40, 0..1_i64 << 40), 0..6)
+            .prop_map(|races| render(&races))
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in races_text()) {
+            prop_assert_eq!(render(&parse(&lines).unwrap()), lines);
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&input::<Day6>(6, InputVariant::Example)), 288.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(
+            part2(&input::<Day6>(6, InputVariant::Example)),
+            71503.into()
+        );
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day6>(6);
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::BTreeMap;
+
+use log::trace;
+
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::util;
+
+#[derive(Eq, PartialEq, PartialOrd, Ord, Debug)]
+enum HandType {
+    FiveOfAKind = 7,
+    FourOfAKind = 6,
+    FullHouse = 5,
+    ThreeOfAKind = 4,
+    TwoPair = 3,
+    OnePair = 2,
+    HighCard = 1,
+}
+type Hand = Vec<i32>;
+
+#[derive(Debug)]
+struct ParsedHand {
+    hand: Hand,
+    // Only shown in the trace output
+    #[allow(dead_code)]
+    hand_text: String,
+    hand_type: HandType,
+    bid: i64,
+}
+
+fn total_winnings(all_hands: &[ParsedHand]) -> i64 {
+    all_hands.iter().enumerate().fold(0_i64, |acc, e| {
+        let rank = e.0 + 1;
+        acc + (rank as i64) * e.1.bid
+    })
+}
+
+fn parse(lines: &[String]) -> Result<Vec<(String, i64)>> {
+    util::parse_lines(lines, |l| {
+        let (hand, bid) = l
+            .split_once(' ')
+            .ok_or_else(|| Error::expected("'<hand> <bid>'"))?;
+        if hand.chars().count() != 5 {
+            return Err(Error::expected("a hand of 5 cards").at_column(0));
+        }
+        if let Some(col) = hand.chars().position(|c| !"23456789TJQKA".contains(c)) {
+            return Err(Error::expected("a card (2-9, T, J, Q, K, A)").at_column(col));
+        }
+        let bid = bid
+            .parse()
+            .map_err(|_| Error::expected_at(l, bid, "a bid"))?;
+        Ok((hand.to_string(), bid))
+    })
+}
+
+fn part1(input: &[(String, i64)]) -> Answer {
+    fn determine_hand_type(hand: &Hand) -> HandType {
+        let mut hand_bins = BTreeMap::new();
+        for card in hand {
+            let def = 0;
+            let old_val = hand_bins.get(&card).unwrap_or(&def);
+            hand_bins.insert(card, old_val + 1);
+        }
+        if hand_bins.values().any(|count| *count == 5) {
+            return HandType::FiveOfAKind;
+        }
+        if hand_bins.values().any(|count| *count == 4) {
+            return HandType::FourOfAKind;
+        }
+        let pairs = hand_bins.values().filter(|count| **count == 2);
+        let pair_count = pairs.count();
+        if hand_bins.values().any(|count| *count == 3) {
+            if pair_count == 1 {
+                return HandType::FullHouse;
+            }
+            return HandType::ThreeOfAKind;
+        }
+        if pair_count == 2 {
+            return HandType::TwoPair;
+        }
+        if pair_count == 1 {
+            return HandType::OnePair;
+        }
+        HandType::HighCard
+    }
+
+    fn parse_hand(hand: &str) -> Hand {
+        hand.chars()
+            .map(|c| match c {
+                '2' => 2,
+                '3' => 3,
+                '4' => 4,
+                '5' => 5,
+                '6' => 6,
+                '7' => 7,
+                '8' => 8,
+                '9' => 9,
+                'T' => 10,
+                'J' => 11,
+                'Q' => 12,
+                'K' => 13,
+                'A' => 14,
+                _ => panic!(),
+            })
+            .collect()
+    }
+
+    let mut all_hands: Vec<ParsedHand> = input
+        .iter()
+        .map(|(hand_str, bid)| {
+            let hand = parse_hand(hand_str);
+            let hand_type = determine_hand_type(&hand);
+
+            ParsedHand {
+                hand,
+                hand_text: hand_str.clone(),
+                hand_type,
+                bid: *bid,
+            }
+        })
+        .collect();
+
+    for hand in &all_hands {
+        trace!("- {:?}", hand);
+    }
+    trace!("---");
+
+    all_hands.sort_unstable_by(|a, b| {
+        if a.hand_type == b.hand_type {
+            let first_ineq_card = a
+                .hand
+                .iter()
+                .zip(b.hand.iter())
+                .find(|(card_a, card_b)| card_a != card_b)
+                .unwrap();
+
+            return first_ineq_card.0.cmp(first_ineq_card.1);
+        }
+        a.hand_type.cmp(&b.hand_type)
+    });
+
+    for hand in &all_hands {
+        trace!("- {:?}", hand);
+    }
+    total_winnings(&all_hands).into()
+}
+
+fn part2(input: &[(String, i64)]) -> Answer {
+    const JOKER: i32 = 1;
+
+    fn determine_hand_type(hand: &Hand) -> HandType {
+        let mut hand_bins = BTreeMap::new();
+        for &card in hand {
+            hand_bins
+                .entry(card)
+                .and_modify(|c| {
+                    *c += 1;
+                })
+                .or_insert(1);
+        }
+        let jokers = hand_bins.remove(&JOKER).unwrap_or(0);
+        let (&best_card, &best_card_count) = hand_bins
+            .iter()
+            .max_by_key(|(_, &v)| v)
+            .unwrap_or((&JOKER, &0)) // only jokers
+            ;
+        hand_bins.insert(best_card, best_card_count + jokers);
+        let mut sorted_counts: Vec<_> = hand_bins.values().collect();
+        sorted_counts.sort_unstable_by(|a, b| b.cmp(a));
+        match sorted_counts[0] {
+            5 => HandType::FiveOfAKind,
+            4 => HandType::FourOfAKind,
+            3 => match sorted_counts[1] {
+                2 => HandType::FullHouse,
+                1 => HandType::ThreeOfAKind,
+                _ => panic!(),
+            },
+            2 => match sorted_counts[1] {
+                2 => HandType::TwoPair,
+                1 => HandType::OnePair,
+                _ => panic!(),
+            },
+            1 => HandType::HighCard,
+            _ => panic!(),
+        }
+    }
+
+    fn parse_hand(hand: &str) -> Hand {
+        hand.chars()
+            .map(|c| match c {
+                '2'..='9' => c.to_digit(10).unwrap() as i32,
+                'T' => 10,
+                'J' => JOKER,
+                'Q' => 12,
+                'K' => 13,
+                'A' => 14,
+                _ => panic!(),
+            })
+            .collect()
+    }
+
+    let mut all_hands: Vec<ParsedHand> = input
+        .iter()
+        .map(|(hand_str, bid)| {
+            let hand = parse_hand(hand_str);
+            let hand_type = determine_hand_type(&hand);
+
+            ParsedHand {
+                hand,
+                hand_text: hand_str.clone(),
+                hand_type,
+                bid: *bid,
+            }
+        })
+        .collect();
+
+    for hand in &all_hands {
+        trace!("- {:?}", hand);
+    }
+    trace!("---");
+
+    all_hands.sort_unstable_by(|a, b| {
+        if a.hand_type == b.hand_type {
+            return a.hand.cmp(&b.hand);
+        }
+        a.hand_type.cmp(&b.hand_type)
+    });
+
+    for hand in &all_hands {
+        trace!("- {:?}", hand);
+    }
+    total_winnings(&all_hands).into()
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    /// Hand text and bid
+    type Input = Vec<(String, i64)>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, input};
+
+    fn render(hands: &[(String, i64)]) -> Vec<String> {
+        hands
+            .iter()
+            .map(|(hand, bid)| format!("{} {}", hand, bid))
+            .collect()
+    }
+
+    fn hands_text() -> impl Strategy<Value = Vec<String>> {
+        let hand = "[2-9TJQKA]{5}";
+        prop::collection::vec((hand, 0..10000_i64), 0..8).prop_map(|hands| render(&hands))
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in hands_text()) {
+            prop_assert_eq!(render(&parse(&lines).unwrap()), lines);
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&input::<Day7>(7, InputVariant::Example)), 6440.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&input::<Day7>(7, InputVariant::Example)), 5905.into());
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day7>(7);
+    }
+}
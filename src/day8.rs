@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use log::debug;
+use regex::Regex;
+
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+
+#[derive(Debug)]
+pub struct Node {
+    next_l: String,
+    next_r: String,
+}
+
+impl Node {
+    fn next(&self, lr: char) -> &String {
+        match lr {
+            'L' => &self.next_l,
+            'R' => &self.next_r,
+            _ => panic!(),
+        }
+    }
+}
+
+pub struct Network {
+    lr: Vec<char>,
+    node_map: BTreeMap<String, Node>,
+}
+
+fn parse(lines: &[String]) -> Result<Network> {
+    let lr_line = lines.first().map_or("", |l| l.as_str());
+    if lr_line.is_empty() {
+        return Err(Error::expected("left/right instructions").in_line(0));
+    }
+    if let Some(col) = lr_line.chars().position(|c| c != 'L' && c != 'R') {
+        return Err(Error::expected("'L' or 'R'").in_line(0).at_column(col));
+    }
+    let lr: Vec<_> = lr_line.chars().collect();
+
+    let instr_re = Regex::new(r"^(\w+) = \((\w+), (\w+)\)$").unwrap();
+
+    let mut node_map: BTreeMap<String, Node> = BTreeMap::new();
+
+    for (i, instr) in lines.iter().enumerate().skip(2) {
+        let (_, [this_node, next_node_l, next_node_r]) = instr_re
+            .captures(instr)
+            .ok_or_else(|| Error::expected("'<node> = (<left>, <right>)'").in_line(i))?
+            .extract();
+        node_map.insert(
+            this_node.to_string(),
+            Node {
+                next_l: next_node_l.to_string(),
+                next_r: next_node_r.to_string(),
+            },
+        );
+    }
+
+    Ok(Network { lr, node_map })
+}
+
+fn part1(network: &Network) -> Answer {
+    let mut lr_iter = network.lr.iter().cycle();
+
+    let mut cur_node = "AAA".to_string();
+    let mut steps = 0_u64;
+    while cur_node != "ZZZ" {
+        let instr = network.node_map.get(cur_node.as_str()).unwrap();
+        let next_lr = lr_iter.next().unwrap();
+        cur_node = instr.next(*next_lr).to_string();
+        steps += 1;
+    }
+    steps.into()
+}
+
+fn part2(network: &Network) -> Answer {
+    let start_nodes: Vec<_> = network
+        .node_map
+        .keys()
+        .filter(|k| k.ends_with('A'))
+        .collect();
+
+    // Walking all ghosts at the same time takes forever. Every ghost runs in a cycle that
+    // ends on its Z node, so all of them meet after the LCM of the cycle lengths.
+    let mut lcm = 1_u64;
+    for (i, start_node) in start_nodes.iter().enumerate() {
+        let mut lr_iter = network.lr.iter().cycle();
+        let mut cur_node = *start_node;
+        let mut steps = 0_u64;
+        while !cur_node.ends_with('Z') {
+            let instr = network.node_map.get(cur_node.as_str()).unwrap();
+            cur_node = instr.next(*lr_iter.next().unwrap());
+            steps += 1;
+        }
+        debug!("[{}] {}: OK in step {}", i, cur_node, steps);
+        lcm = num::Integer::lcm(&lcm, &steps);
+    }
+    lcm.into()
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Network;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, input};
+
+    /// Instructions, then the nodes sorted by name, the order they are kept in
+    fn render(network: &Network) -> Vec<String> {
+        let mut lines = vec![String::from_iter(&network.lr), String::new()];
+        lines.extend(
+            network
+                .node_map
+                .iter()
+                .map(|(name, node)| format!("{} = ({}, {})", name, node.next_l, node.next_r)),
+        );
+        lines
+    }
+
+    fn network_text() -> impl Strategy<Value = Vec<String>> {
+        let name = || "[0-9A-Z]{3}";
+        let nodes = prop::collection::btree_map(name(), (name(), name()), 0..8);
+        ("[LR]{1,10}", nodes).prop_map(|(lr, nodes)| {
+            let mut lines = vec![lr, String::new()];
+            lines.extend(
+                nodes
+                    .iter()
+                    .map(|(name, (left, right))| format!("{} = ({}, {})", name, left, right)),
+            );
+            lines
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in network_text()) {
+            prop_assert_eq!(render(&parse(&lines).unwrap()), lines);
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&input::<Day8>(8, InputVariant::Example)), 2.into());
+    }
+
+    #[test]
+    fn part2_input3() {
+        assert_eq!(part2(&input::<Day8>(8, InputVariant::Extra(3))), 6.into());
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day8>(8);
+    }
+}
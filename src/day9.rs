@@ -0,0 +1,124 @@
+use log::trace;
+
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::util;
+
+/// All difference sequences down to the one that is all zeros
+fn build_histories(nums: &[i32]) -> Vec<Vec<i32>> {
+    let mut histories = vec![nums.to_vec()];
+    while !histories.last().unwrap().iter().all(|&c| c == 0) {
+        let this = histories.last().unwrap();
+        let pairs = this.windows(2);
+        let diffs = pairs.map(|s| s[1] - s[0]).collect();
+        histories.push(diffs);
+    }
+    histories
+}
+
+fn parse(lines: &[String]) -> Result<Vec<Vec<i32>>> {
+    util::parse_lines(lines, |line| {
+        line.split_whitespace()
+            .map(|s| {
+                s.parse()
+                    .map_err(|_| Error::expected_at(line, s, "a number"))
+            })
+            .collect()
+    })
+}
+
+fn part1(input: &[Vec<i32>]) -> Answer {
+    let next_vals = input.iter().map(|nums| {
+        let mut histories = build_histories(nums);
+        // FIXME for loop with negative step?
+        for i in 0..histories.len() - 1 {
+            let len = histories.len();
+            // FIXME ugly :( but it doesn't seem to be possible to borrow more than one array item
+            let mut iter_mut = histories.iter_mut();
+            let before = iter_mut.nth(len - i - 2).unwrap();
+            let this = iter_mut.next().unwrap();
+            let new_el = this.last().unwrap() + before.last().unwrap();
+            before.push(new_el);
+        }
+        trace!("{:?}", histories);
+        *histories[0].last().unwrap()
+    });
+    let sum: i32 = next_vals.sum();
+    sum.into()
+}
+
+fn part2(input: &[Vec<i32>]) -> Answer {
+    let next_vals = input.iter().map(|nums| {
+        let mut histories = build_histories(nums);
+        for i in num::range_step((histories.len() - 1) as i32, 0, -1) {
+            let [before, this] = histories
+                .get_disjoint_mut([i as usize - 1, i as usize])
+                .unwrap();
+            let new_el = -this[0] + before[0];
+            before.insert(0, new_el);
+        }
+        trace!("{:?}", histories);
+        histories[0][0]
+    });
+    let sum: i32 = next_vals.sum();
+    sum.into()
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Vec<Vec<i32>>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::input::InputVariant;
+    use crate::testing::{check_real, input};
+
+    fn render(histories: &[Vec<i32>]) -> Vec<String> {
+        histories.iter().map(|nums| nums.iter().join(" ")).collect()
+    }
+
+    fn histories_text() -> impl Strategy<Value = Vec<String>> {
+        let history = prop::collection::vec(-1_000_000..1_000_000_i32, 1..20);
+        prop::collection::vec(history, 0..8).prop_map(|histories| render(&histories))
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trip(lines in histories_text()) {
+            prop_assert_eq!(render(&parse(&lines).unwrap()), lines);
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&input::<Day9>(9, InputVariant::Example)), 114.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&input::<Day9>(9, InputVariant::Example)), 2.into());
+    }
+
+    #[test]
+    fn real() {
+        check_real::<Day9>(9);
+    }
+}
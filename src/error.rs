@@ -0,0 +1,121 @@
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+/// Errors while reading or parsing puzzle input
+#[derive(Debug)]
+pub enum Error {
+    /// Input could not be read
+    Io { path: PathBuf, source: io::Error },
+    /// Input is malformed; line and column are 1-based
+    Parse {
+        file: Option<PathBuf>,
+        line: Option<usize>,
+        column: Option<usize>,
+        expected: String,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// Malformed input, the position is filled in by the callers with [`Error::in_line`] etc.
+    pub fn expected(what: impl Into<String>) -> Error {
+        Error::Parse {
+            file: None,
+            line: None,
+            column: None,
+            expected: what.into(),
+        }
+    }
+
+    /// Malformed input at `token`, which has to be a slice of `line`
+    pub fn expected_at(line: &str, token: &str, what: impl Into<String>) -> Error {
+        let offset = (token.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+        let err = Error::expected(what);
+        match line.get(..offset) {
+            Some(before) if offset <= line.len() => err.at_column(before.chars().count()),
+            _ => err,
+        }
+    }
+
+    /// Set the 0-based column index unless it is already known
+    pub fn at_column(mut self, index: usize) -> Error {
+        if let Error::Parse { column, .. } = &mut self {
+            column.get_or_insert(index + 1);
+        }
+        self
+    }
+
+    /// Set the 0-based line index unless it is already known
+    pub fn in_line(mut self, index: usize) -> Error {
+        if let Error::Parse { line, .. } = &mut self {
+            line.get_or_insert(index + 1);
+        }
+        self
+    }
+
+    /// Move the line by `count` lines, for errors from parsing a block of the input on its own
+    pub fn offset_lines(mut self, count: usize) -> Error {
+        if let Error::Parse {
+            line: Some(line), ..
+        } = &mut self
+        {
+            *line += count;
+        }
+        self
+    }
+
+    /// Set the input file unless it is already known
+    pub fn in_file(mut self, path: &Path) -> Error {
+        if let Error::Parse { file, .. } = &mut self {
+            file.get_or_insert_with(|| path.to_path_buf());
+        }
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "cannot read {}: {}", path.display(), source),
+            Error::Parse {
+                file,
+                line,
+                column,
+                expected,
+            } => {
+                let file = file
+                    .as_ref()
+                    .map_or("<input>".into(), |p| p.display().to_string());
+                match (line, column) {
+                    (Some(line), Some(column)) => write!(f, "{}:{}:{}", file, line, column)?,
+                    (Some(line), None) => write!(f, "{}:{}", file, line)?,
+                    _ => write!(f, "{}", file)?,
+                }
+                write!(f, ": expected {}", expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Parse { .. } => None,
+        }
+    }
+}
+
+/// Turn a missing value into a parse error
+pub trait OrExpected<T> {
+    fn or_expected(self, what: &str) -> Result<T>;
+}
+
+impl<T> OrExpected<T> for Option<T> {
+    fn or_expected(self, what: &str) -> Result<T> {
+        self.ok_or_else(|| Error::expected(what))
+    }
+}
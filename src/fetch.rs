@@ -0,0 +1,328 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::Args;
+
+use crate::http::Http;
+use crate::input::{InputVariant, Inputs};
+
+/// Environment variable with the session token, takes precedence over the session file
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Session file used if none is given: `aoc/session` in the user config directory
+pub fn default_session_file() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("aoc").join("session"))
+}
+
+/// Input cache used if none is given: `aoc` in the user cache directory
+pub fn default_cache_root() -> Option<PathBuf> {
+    Some(dirs::cache_dir()?.join("aoc"))
+}
+
+/// Directory with the cached inputs of `user` for `year`
+///
+/// The cache is laid out as an input tree, so the inputs can also be used from there with
+/// `--input-dir <cache root> --owner <user>`.
+pub fn cache_dir(cache_root: &Path, year: u16, user: &str) -> PathBuf {
+    cache_root.join(year.to_string()).join(user)
+}
+
+/// Session token from `var`, the value of [`SESSION_VAR`], or else from the first line of
+/// `session_file`
+pub fn session_token(var: Option<String>, session_file: Option<&Path>) -> Result<String, String> {
+    if let Some(token) = var.filter(|t| !t.trim().is_empty()) {
+        return Ok(token.trim().to_string());
+    }
+    let path = session_file.ok_or_else(|| format!("No session token, set {}", SESSION_VAR))?;
+    let text = fs::read_to_string(path).map_err(|e| {
+        format!(
+            "No session token, set {} or write it to {} ({})",
+            SESSION_VAR,
+            path.display(),
+            e
+        )
+    })?;
+    match text.lines().next().map(str::trim) {
+        Some(token) if !token.is_empty() => Ok(token.to_string()),
+        _ => Err(format!("Session file {} is empty", path.display())),
+    }
+}
+
+/// Name of the cache of the account if no owner is given
+const DEFAULT_OWNER: &str = "default";
+
+/// Advent of Code account to fetch inputs and submit answers with, the inputs and submissions
+/// are kept per owner
+#[derive(Debug, Clone, Args)]
+pub struct Account {
+    /// Root directory of the input cache [default: aoc in the user cache directory]
+    #[arg(long, env = "AOC_CACHE_DIR")]
+    pub cache_dir: Option<PathBuf>,
+    /// File with the session token [default: aoc/session in the user config directory]
+    #[arg(long)]
+    pub session_file: Option<PathBuf>,
+}
+
+impl Account {
+    /// Directory with the cached inputs and the submission history of `owner` for `year`
+    pub fn cache_dir(&self, year: u16, owner: Option<&str>) -> Result<PathBuf, String> {
+        let cache_root = self
+            .cache_dir
+            .clone()
+            .or_else(default_cache_root)
+            .ok_or("No user cache directory, give one with --cache-dir")?;
+        Ok(cache_dir(&cache_root, year, owner.unwrap_or(DEFAULT_OWNER)))
+    }
+
+    /// Session token from [`SESSION_VAR`] or the session file
+    pub fn session(&self) -> Result<String, String> {
+        let session_file = self.session_file.clone().or_else(default_session_file);
+        session_token(std::env::var(SESSION_VAR).ok(), session_file.as_deref())
+    }
+}
+
+/// Where the input of a fetch came from
+#[derive(Debug, PartialEq, Eq)]
+pub enum Fetched {
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// Path of the real input of `day` in `cache_dir`, downloaded first if it is not there yet
+///
+/// Inputs never change, so a cached input is never downloaded again. `session` is only asked
+/// for if a download is needed.
+pub fn fetch_input(
+    http: &dyn Http,
+    cache_dir: &Path,
+    year: u16,
+    day: u8,
+    session: impl FnOnce() -> Result<String, String>,
+) -> Result<Fetched, String> {
+    let path = cache_dir.join(InputVariant::Real.file_name(day));
+    if path.exists() {
+        return Ok(Fetched::Cached(path));
+    }
+
+    let url_path = format!("/{}/day/{}/input", year, day);
+    let response = http.get(&url_path, &session()?)?;
+    let reason = response.body.lines().next().unwrap_or("").trim();
+    match response.status {
+        200 if !response.body.is_empty() => {}
+        200 => return Err(format!("Empty input received for day {}", day)),
+        404 => return Err(format!("Day {} is not unlocked yet: {}", day, reason)),
+        400 | 500 => {
+            return Err(format!(
+                "Session token was rejected ({}): {}",
+                response.status, reason
+            ))
+        }
+        status => return Err(format!("Unexpected response {} for {}", status, url_path)),
+    }
+
+    fs::create_dir_all(cache_dir)
+        .map_err(|e| format!("Cannot create {}: {}", cache_dir.display(), e))?;
+    // Write under another name first, so that an interrupted write is never taken as cached
+    let partial_path = path.with_extension("part");
+    fs::write(&partial_path, &response.body)
+        .map_err(|e| format!("Cannot write {}: {}", partial_path.display(), e))?;
+    fs::rename(&partial_path, &path)
+        .map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+    Ok(Fetched::Downloaded(path))
+}
+
+/// Whether `target` holds an input, and not just the empty placeholder `new` creates
+fn is_installed(target: &Path) -> bool {
+    fs::metadata(target).is_ok_and(|m| m.len() > 0)
+}
+
+/// Copy a fetched input to `target` unless it is there already
+///
+/// Refuses to overwrite a different file, other than an empty placeholder. Returns whether
+/// `target` was written.
+pub fn install_input(fetched: &Path, target: &Path) -> Result<bool, String> {
+    let read =
+        |path: &Path| fs::read(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e));
+    let input = read(fetched)?;
+    if is_installed(target) {
+        return match read(target)? == input {
+            true => Ok(false),
+            false => Err(format!(
+                "{} already exists with different content, not overwritten",
+                target.display()
+            )),
+        };
+    }
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+    }
+    fs::write(target, input).map_err(|e| format!("Cannot write {}: {}", target.display(), e))?;
+    Ok(true)
+}
+
+/// Fetch the real input of `day` into the cache of `account` and install it into `inputs`,
+/// unless it is installed already
+///
+/// Prints where the input was downloaded to and installed.
+pub fn fetch_day(
+    http: &dyn Http,
+    account: &Account,
+    inputs: &Inputs,
+    day: u8,
+) -> Result<(), String> {
+    let target = inputs.input_path(day, InputVariant::Real);
+    if is_installed(&target) {
+        println!("{} is there already", target.display());
+        return Ok(());
+    }
+    let cache_dir = account.cache_dir(inputs.year, inputs.owner.as_deref())?;
+    let fetched = match fetch_input(http, &cache_dir, inputs.year, day, || account.session())? {
+        Fetched::Cached(path) => path,
+        Fetched::Downloaded(path) => {
+            println!("Downloaded {}", path.display());
+            path
+        }
+    };
+    match install_input(&fetched, &target)? {
+        true => println!("{}", target.display()),
+        false => println!("{} is up to date", target.display()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::http::{FakeHttp, Request};
+
+    const YEAR: u16 = 2023;
+
+    fn session() -> Result<String, String> {
+        Ok("abc".to_string())
+    }
+
+    #[test]
+    fn downloads_once() {
+        let tmp = TempDir::new().unwrap();
+        let dir = cache_dir(tmp.path(), YEAR, "me");
+        let http = FakeHttp::new(200, "1 2 3\n");
+
+        let path = dir.join("inp7_2.txt");
+        assert_eq!(
+            fetch_input(&http, &dir, YEAR, 7, session),
+            Ok(Fetched::Downloaded(path.clone()))
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1 2 3\n");
+        assert_eq!(
+            *http.requests.borrow(),
+            [Request {
+                method: "GET",
+                path: "/2023/day/7/input".to_string(),
+                session: "abc".to_string(),
+                form: Vec::new(),
+            }]
+        );
+
+        let no_session = || Err("session must not be needed".to_string());
+        assert_eq!(
+            fetch_input(&http, &dir, YEAR, 7, no_session),
+            Ok(Fetched::Cached(path))
+        );
+        assert_eq!(http.requests.borrow().len(), 1);
+    }
+
+    #[test]
+    fn caches_per_user() {
+        let tmp = TempDir::new().unwrap();
+        let http = FakeHttp::new(200, "1 2 3\n");
+        for user in ["alice", "bob"] {
+            let dir = cache_dir(tmp.path(), YEAR, user);
+            assert!(matches!(
+                fetch_input(&http, &dir, YEAR, 7, session),
+                Ok(Fetched::Downloaded(_))
+            ));
+        }
+        assert_eq!(http.requests.borrow().len(), 2);
+    }
+
+    #[test]
+    fn errors_are_not_cached() {
+        let tmp = TempDir::new().unwrap();
+        let dir = cache_dir(tmp.path(), YEAR, "me");
+        for (status, message) in [
+            (404, "not unlocked yet"),
+            (400, "rejected"),
+            (500, "rejected"),
+            (200, "Empty input"),
+        ] {
+            let body = match status {
+                200 => "",
+                _ => "Please log in to get your puzzle input.\n",
+            };
+            let http = FakeHttp::new(status, body);
+            let e = fetch_input(&http, &dir, YEAR, 7, session).unwrap_err();
+            assert!(e.contains(message), "{}", e);
+            assert!(!dir.join("inp7_2.txt").exists());
+        }
+    }
+
+    #[test]
+    fn session_from_var_or_file() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("session");
+        fs::write(&file, "fromfile\n").unwrap();
+
+        assert_eq!(
+            session_token(Some("fromvar".to_string()), Some(&file)),
+            Ok("fromvar".to_string())
+        );
+        assert_eq!(session_token(None, Some(&file)), Ok("fromfile".to_string()));
+        assert_eq!(
+            session_token(Some(" ".to_string()), Some(&file)),
+            Ok("fromfile".to_string())
+        );
+        assert!(session_token(None, Some(&tmp.path().join("missing"))).is_err());
+        assert!(session_token(None, None).is_err());
+    }
+
+    #[test]
+    fn install_keeps_different_input() {
+        let tmp = TempDir::new().unwrap();
+        let fetched = tmp.path().join("fetched.txt");
+        let target = tmp.path().join("inp7_2.txt");
+        fs::write(&fetched, "1 2 3\n").unwrap();
+
+        assert_eq!(install_input(&fetched, &target), Ok(true));
+        assert_eq!(install_input(&fetched, &target), Ok(false));
+        fs::write(&target, "4 5 6\n").unwrap();
+        assert!(install_input(&fetched, &target).is_err());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "4 5 6\n");
+        // The empty placeholder of a new day is replaced
+        fs::write(&target, "").unwrap();
+        assert_eq!(install_input(&fetched, &target), Ok(true));
+        assert_eq!(fs::read_to_string(&target).unwrap(), "1 2 3\n");
+    }
+
+    #[test]
+    fn installed_input_is_not_fetched() {
+        let tmp = TempDir::new().unwrap();
+        let inputs = Inputs::flat(tmp.path(), YEAR);
+        let account = Account {
+            cache_dir: Some(tmp.path().join("cache")),
+            session_file: Some(tmp.path().join("missing")),
+        };
+        let http = FakeHttp::new(200, "1 2 3\n");
+        fs::write(tmp.path().join("inp7_2.txt"), "4 5 6\n").unwrap();
+
+        assert_eq!(fetch_day(&http, &account, &inputs, 7), Ok(()));
+        assert!(http.requests.borrow().is_empty());
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("inp7_2.txt")).unwrap(),
+            "4 5 6\n"
+        );
+    }
+}
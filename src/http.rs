@@ -0,0 +1,217 @@
+use std::io::Read;
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+/// Identifies the tool to the Advent of Code website, as its operator asks for
+const USER_AGENT: &str = "github.com/yol/aoc2023";
+
+/// Status and body of a response of the website
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+/// Requests to the Advent of Code website, behind a trait so that tests don't need the network
+pub trait Http {
+    /// GET `path` (e.g. `/2023/day/1/input`) as the user logged in with `session`
+    fn get(&self, path: &str, session: &str) -> Result<Response, String>;
+
+    /// POST the form fields `form` to `path` as the user logged in with `session`
+    fn post(&self, path: &str, session: &str, form: &[(&str, &str)]) -> Result<Response, String>;
+}
+
+/// The real website, or anything else serving the same paths at `base_url`
+pub struct UreqHttp {
+    base_url: String,
+    agent: ureq::Agent,
+}
+
+impl UreqHttp {
+    pub fn new(base_url: &str) -> UreqHttp {
+        UreqHttp {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+        }
+    }
+}
+
+/// Responses with an error status are still responses of the website, only failing to reach it
+/// is an error
+fn into_result(url: &str, result: Result<ureq::Response, ureq::Error>) -> Result<Response, String> {
+    let response = match result {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+        Err(e) => return Err(format!("Cannot request {}: {}", url, e)),
+    };
+    let status = response.status();
+    let mut body = String::new();
+    response
+        .into_reader()
+        .read_to_string(&mut body)
+        .map_err(|e| format!("Cannot read response: {}", e))?;
+    Ok(Response { status, body })
+}
+
+impl Http for UreqHttp {
+    fn get(&self, path: &str, session: &str) -> Result<Response, String> {
+        let url = format!("{}{}", self.base_url, path);
+        let result = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", session))
+            .call();
+        into_result(&url, result)
+    }
+
+    fn post(&self, path: &str, session: &str, form: &[(&str, &str)]) -> Result<Response, String> {
+        let url = format!("{}{}", self.base_url, path);
+        let result = self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", session))
+            .send_form(form);
+        into_result(&url, result)
+    }
+}
+
+/// Record of a request to [`FakeHttp`]
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: &'static str,
+    pub path: String,
+    pub session: String,
+    pub form: Vec<(String, String)>,
+}
+
+/// Answers every request with the same response and records the requests
+#[cfg(test)]
+pub struct FakeHttp {
+    response: Response,
+    pub requests: std::cell::RefCell<Vec<Request>>,
+}
+
+#[cfg(test)]
+impl FakeHttp {
+    pub fn new(status: u16, body: &str) -> FakeHttp {
+        FakeHttp {
+            response: Response {
+                status,
+                body: body.to_string(),
+            },
+            requests: Default::default(),
+        }
+    }
+
+    fn respond(
+        &self,
+        method: &'static str,
+        path: &str,
+        session: &str,
+        form: &[(&str, &str)],
+    ) -> Result<Response, String> {
+        self.requests.borrow_mut().push(Request {
+            method,
+            path: path.to_string(),
+            session: session.to_string(),
+            form: form
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        });
+        Ok(self.response.clone())
+    }
+}
+
+#[cfg(test)]
+impl Http for FakeHttp {
+    fn get(&self, path: &str, session: &str) -> Result<Response, String> {
+        self.respond("GET", path, session, &[])
+    }
+
+    fn post(&self, path: &str, session: &str, form: &[(&str, &str)]) -> Result<Response, String> {
+        self.respond("POST", path, session, form)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn get_sends_session_cookie() {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let handle = thread::spawn(move || {
+            let request = server.recv().unwrap();
+            let cookie = request
+                .headers()
+                .iter()
+                .find(|h| h.field.equiv("Cookie"))
+                .map(|h| h.value.to_string());
+            let url = request.url().to_string();
+            let response = tiny_http::Response::from_string("1\n2\n").with_status_code(200);
+            request.respond(response).unwrap();
+            (url, cookie)
+        });
+
+        let response = UreqHttp::new(&base_url)
+            .get("/2023/day/1/input", "abc")
+            .unwrap();
+        assert_eq!(
+            response,
+            Response {
+                status: 200,
+                body: "1\n2\n".to_string()
+            }
+        );
+        let (url, cookie) = handle.join().unwrap();
+        assert_eq!(url, "/2023/day/1/input");
+        assert_eq!(cookie.as_deref(), Some("session=abc"));
+    }
+
+    #[test]
+    fn post_sends_form() {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let handle = thread::spawn(move || {
+            let mut request = server.recv().unwrap();
+            let mut body = String::new();
+            request.as_reader().read_to_string(&mut body).unwrap();
+            let method = request.method().to_string();
+            request
+                .respond(tiny_http::Response::from_string("ok"))
+                .unwrap();
+            (method, body)
+        });
+
+        let form = [("level", "1"), ("answer", "42")];
+        let response = UreqHttp::new(&base_url)
+            .post("/2023/day/1/answer", "abc", &form)
+            .unwrap();
+        assert_eq!(response.body, "ok");
+        let (method, body) = handle.join().unwrap();
+        assert_eq!(method, "POST");
+        assert_eq!(body, "level=1&answer=42");
+    }
+
+    #[test]
+    fn get_returns_error_status() {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let handle = thread::spawn(move || {
+            let request = server.recv().unwrap();
+            let response = tiny_http::Response::from_string("Not yet").with_status_code(404);
+            request.respond(response).unwrap();
+        });
+
+        let response = UreqHttp::new(&base_url)
+            .get("/2023/day/25/input", "abc")
+            .unwrap();
+        assert_eq!(response.status, 404);
+        assert_eq!(response.body, "Not yet");
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,378 @@
+use std::{
+    any::Any,
+    fmt, fs,
+    io::{self, BufRead},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::answers::Answers;
+use crate::error::{self, Error};
+use crate::solution::DynSolution;
+use crate::util::file_lines;
+
+/// Which of the input files of a day to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum InputVariant {
+    /// Example from the puzzle description (`inpN_1.txt`)
+    Example,
+    /// Personal puzzle input (`inpN_2.txt`)
+    Real,
+    /// Any additional input, e.g. a second example (`inpN_3.txt` for `Extra(3)`)
+    Extra(u8),
+}
+
+impl InputVariant {
+    fn file_index(self) -> u8 {
+        match self {
+            InputVariant::Example => 1,
+            InputVariant::Real => 2,
+            InputVariant::Extra(i) => i,
+        }
+    }
+
+    fn from_file_index(i: u8) -> InputVariant {
+        match i {
+            1 => InputVariant::Example,
+            2 => InputVariant::Real,
+            i => InputVariant::Extra(i),
+        }
+    }
+
+    pub fn file_name(self, day: u8) -> String {
+        format!("inp{}_{}.txt", day, self.file_index())
+    }
+
+    /// Variant of an input file name of `day`, the reverse of [`InputVariant::file_name`]
+    pub fn from_file_name(day: u8, name: &str) -> Option<InputVariant> {
+        let index = name
+            .strip_prefix(&format!("inp{}_", day))?
+            .strip_suffix(".txt")?;
+        match index.parse::<u8>() {
+            Ok(i) if i >= 1 => Some(InputVariant::from_file_index(i)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for InputVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputVariant::Example => write!(f, "example"),
+            InputVariant::Real => write!(f, "real"),
+            InputVariant::Extra(i) => write!(f, "{}", i),
+        }
+    }
+}
+
+impl FromStr for InputVariant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "example" => Ok(InputVariant::Example),
+            "real" => Ok(InputVariant::Real),
+            _ => match s.parse::<u8>() {
+                Ok(i) if i >= 1 => Ok(InputVariant::from_file_index(i)),
+                _ => Err(format!(
+                    "invalid input variant '{}', expected 'example', 'real' or a file number",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+/// Where to read the puzzle input from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputSource {
+    File(InputVariant),
+    Stdin,
+}
+
+impl FromStr for InputSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "-" => Ok(InputSource::Stdin),
+            _ => s.parse().map(InputSource::File),
+        }
+    }
+}
+
+/// File with the expected answers next to the inputs
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Input files and answers of one owner for one puzzle year
+///
+/// In the flat layout everything is in one directory, like the root of this repository. In the
+/// tree layout it is in `<root>/<year>/<owner>/`, while files that are the same for everybody,
+/// like the examples, can also be in `<root>/<year>/`.
+#[derive(Debug, Clone)]
+pub struct Inputs {
+    pub year: u16,
+    /// Owner of the inputs in the tree layout
+    pub owner: Option<String>,
+    /// Directories with the files, the most specific first
+    dirs: Vec<PathBuf>,
+}
+
+impl Inputs {
+    pub fn flat(dir: &Path, year: u16) -> Inputs {
+        Inputs {
+            year,
+            owner: None,
+            dirs: vec![dir.to_path_buf()],
+        }
+    }
+
+    pub fn tree(root: &Path, year: u16, owner: &str) -> Inputs {
+        let year_dir = root.join(year.to_string());
+        Inputs {
+            year,
+            owner: Some(owner.to_string()),
+            dirs: vec![year_dir.join(owner), year_dir],
+        }
+    }
+
+    /// Owners with a directory for `year` in the tree at `root`, sorted by name
+    pub fn owners(root: &Path, year: u16) -> Vec<String> {
+        let Ok(dir) = fs::read_dir(root.join(year.to_string())) else {
+            return Vec::new();
+        };
+        let mut owners: Vec<_> = dir
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                match entry.file_type().ok()?.is_dir() {
+                    true => entry.file_name().into_string().ok(),
+                    false => None,
+                }
+            })
+            .collect();
+        owners.sort();
+        owners
+    }
+
+    /// Inputs of every owner with a directory for `year` in the tree at `root`
+    pub fn all_owners(root: &Path, year: u16) -> Result<Vec<Inputs>, String> {
+        let owners = Self::owners(root, year);
+        if owners.is_empty() {
+            let year_dir = root.join(year.to_string());
+            return Err(format!("No owners found in {}", year_dir.display()));
+        }
+        Ok(owners
+            .iter()
+            .map(|owner| Self::tree(root, year, owner))
+            .collect())
+    }
+
+    pub fn dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+
+    /// Directory for files that are the same for all owners of the year
+    pub fn shared_dir(&self) -> &Path {
+        self.dirs.last().unwrap()
+    }
+
+    /// Path of the file `name` in the most specific directory that has it, or in the directory
+    /// of the owner if none has it
+    fn file_path(&self, name: &str) -> PathBuf {
+        self.dirs
+            .iter()
+            .map(|dir| dir.join(name))
+            .find(|path| path.exists())
+            .unwrap_or_else(|| self.dirs[0].join(name))
+    }
+
+    pub fn input_path(&self, day: u8, variant: InputVariant) -> PathBuf {
+        self.file_path(&variant.file_name(day))
+    }
+
+    /// All input variants of `day` that have a file, in file order
+    pub fn available_variants(&self, day: u8) -> Vec<InputVariant> {
+        let mut variants: Vec<_> = self
+            .dirs
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name().into_string().ok()?;
+                InputVariant::from_file_name(day, &name)
+            })
+            .collect();
+        variants.sort();
+        variants.dedup();
+        variants
+    }
+
+    /// Answers of all answers files, an answers table of the owner replaces the same shared one
+    pub fn answers(&self) -> Result<Answers, String> {
+        let mut answers = Answers::default();
+        for dir in self.dirs.iter().rev() {
+            let path = dir.join(ANSWERS_FILE);
+            if path.exists() {
+                answers.extend(Answers::load(&path)?);
+            }
+        }
+        Ok(answers)
+    }
+}
+
+pub fn stdin_lines() -> error::Result<Vec<String>> {
+    io::stdin()
+        .lock()
+        .lines()
+        .collect::<io::Result<_>>()
+        .map_err(|source| Error::Io {
+            path: PathBuf::from("<stdin>"),
+            source,
+        })
+}
+
+/// Read the lines of the input of `day` from `source`
+pub fn read_input(inputs: &Inputs, day: u8, source: InputSource) -> error::Result<Vec<String>> {
+    match source {
+        InputSource::File(variant) => file_lines(inputs.input_path(day, variant)),
+        InputSource::Stdin => stdin_lines(),
+    }
+}
+
+/// Name of the input to use in error messages
+pub fn source_path(inputs: &Inputs, day: u8, source: InputSource) -> PathBuf {
+    match source {
+        InputSource::File(variant) => inputs.input_path(day, variant),
+        InputSource::Stdin => PathBuf::from("<stdin>"),
+    }
+}
+
+/// Read and parse the input of `day` from `source`, errors name the input file
+pub fn load_input(
+    solution: &dyn DynSolution,
+    inputs: &Inputs,
+    day: u8,
+    source: InputSource,
+) -> error::Result<Box<dyn Any>> {
+    let lines = read_input(inputs, day, source)?;
+    solution
+        .parse_any(&lines)
+        .map_err(|e| e.in_file(&source_path(inputs, day, source)))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// Tree with a shared example and answers for 2023, and real inputs of two owners
+    fn tree() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        let year_dir = tmp.path().join("2023");
+        for owner in ["alice", "bob"] {
+            fs::create_dir_all(year_dir.join(owner)).unwrap();
+            fs::write(year_dir.join(owner).join("inp7_2.txt"), owner).unwrap();
+        }
+        fs::write(year_dir.join("inp7_1.txt"), "example").unwrap();
+        fs::write(year_dir.join("inp7_3.txt"), "extra").unwrap();
+        fs::write(
+            year_dir.join(ANSWERS_FILE),
+            "[day7.example]\npart1 = 1\n\n[day7.real]\npart1 = 10\n",
+        )
+        .unwrap();
+        fs::write(
+            year_dir.join("alice").join(ANSWERS_FILE),
+            "[day7.real]\npart1 = 20\npart2 = 21\n",
+        )
+        .unwrap();
+        tmp
+    }
+
+    #[test]
+    fn resolves_owner_before_shared_files() {
+        let tmp = tree();
+        let year_dir = tmp.path().join("2023");
+        let inputs = Inputs::tree(tmp.path(), 2023, "alice");
+
+        assert_eq!(
+            inputs.input_path(7, InputVariant::Real),
+            year_dir.join("alice").join("inp7_2.txt")
+        );
+        assert_eq!(
+            inputs.input_path(7, InputVariant::Example),
+            year_dir.join("inp7_1.txt")
+        );
+        // Missing files belong to the owner
+        assert_eq!(
+            inputs.input_path(8, InputVariant::Example),
+            year_dir.join("alice").join("inp8_1.txt")
+        );
+        assert_eq!(
+            inputs.available_variants(7),
+            [
+                InputVariant::Example,
+                InputVariant::Real,
+                InputVariant::Extra(3)
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_owners() {
+        let tmp = tree();
+        assert_eq!(Inputs::owners(tmp.path(), 2023), ["alice", "bob"]);
+        assert!(Inputs::owners(tmp.path(), 2022).is_empty());
+    }
+
+    #[test]
+    fn merges_answers() {
+        let tmp = tree();
+        let alice = Inputs::tree(tmp.path(), 2023, "alice").answers().unwrap();
+        let bob = Inputs::tree(tmp.path(), 2023, "bob").answers().unwrap();
+
+        assert_eq!(
+            alice
+                .expected(7, InputVariant::Example, 1)
+                .unwrap()
+                .to_string(),
+            "1"
+        );
+        assert_eq!(
+            alice
+                .expected(7, InputVariant::Real, 1)
+                .unwrap()
+                .to_string(),
+            "20"
+        );
+        assert_eq!(
+            alice
+                .expected(7, InputVariant::Real, 2)
+                .unwrap()
+                .to_string(),
+            "21"
+        );
+        assert_eq!(
+            bob.expected(7, InputVariant::Real, 1).unwrap().to_string(),
+            "10"
+        );
+        assert!(bob.expected(7, InputVariant::Real, 2).is_none());
+    }
+
+    #[test]
+    fn flat_layout() {
+        let tmp = tree();
+        let year_dir = tmp.path().join("2023");
+        let inputs = Inputs::flat(&year_dir, 2023);
+
+        assert_eq!(inputs.owner, None);
+        assert_eq!(
+            inputs.input_path(7, InputVariant::Example),
+            year_dir.join("inp7_1.txt")
+        );
+        assert_eq!(
+            inputs.available_variants(7),
+            [InputVariant::Example, InputVariant::Extra(3)]
+        );
+    }
+}
@@ -0,0 +1,85 @@
+//! Advent of Code 2023 solutions, the puzzle helpers in [`util`] and the tooling to run and
+//! check the solvers
+
+use solution::Solutions;
+
+pub mod answers;
+pub mod bench;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod error;
+pub mod fetch;
+pub mod http;
+pub mod input;
+pub mod logging;
+pub mod parallel;
+pub mod repl;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod submit;
+#[cfg(test)]
+mod template;
+#[cfg(test)]
+mod testing;
+pub mod util;
+pub mod verify;
+pub mod watch;
+
+/// Year of the solvers in `SOLUTIONS`
+pub const YEAR: u16 = 2023;
+
+/// Solvers with their days, `new` inserts the days it creates here
+pub const SOLUTIONS: &Solutions = &[
+    (1, &day1::Day1),
+    (2, &day2::Day2),
+    (3, &day3::Day3),
+    (4, &day4::Day4),
+    (5, &day5::Day5),
+    (6, &day6::Day6),
+    (7, &day7::Day7),
+    (8, &day8::Day8),
+    (9, &day9::Day9),
+    (10, &day10::Day10),
+    (11, &day11::Day11),
+    (12, &day12::Day12),
+    (13, &day13::Day13),
+    (14, &day14::Day14),
+    (15, &day15::Day15),
+    (16, &day16::Day16),
+    (17, &day17::Day17),
+    (18, &day18::Day18),
+    (19, &day19::Day19),
+    (20, &day20::Day20),
+    (21, &day21::Day21),
+    (22, &day22::Day22),
+    (23, &day23::Day23),
+    (24, &day24::Day24),
+    (25, &day25::Day25),
+];
+
+/// Solvers of each year
+pub const YEARS: &[(u16, &Solutions)] = &[(YEAR, SOLUTIONS)];
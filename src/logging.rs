@@ -0,0 +1,30 @@
+use log::LevelFilter;
+
+use crate::SOLUTIONS;
+
+/// Log solver diagnostics to stderr according to the verbosity, restricted to `log_days` if
+/// given. `RUST_LOG` can override the filter.
+pub fn init(verbose: u8, log_days: &[u8]) {
+    let level = match verbose {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+    let mut builder = env_logger::Builder::new();
+    if log_days.is_empty() {
+        builder.filter_level(level);
+    } else {
+        builder.filter_level(LevelFilter::Warn);
+        // Module filters match by prefix, so "day1" would include "day12" if not every day
+        // had its own filter
+        for &(day, _) in SOLUTIONS {
+            let day_level = match log_days.contains(&day) {
+                true => level,
+                false => LevelFilter::Warn,
+            };
+            builder.filter_module(&format!("aoc2023::day{}", day), day_level);
+        }
+    }
+    builder.parse_default_env().init();
+}
@@ -0,0 +1,302 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc::{self, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use rayon::ThreadPoolBuilder;
+
+use crate::error::Error;
+use crate::input::{self, InputSource, Inputs};
+use crate::runner::{self, DayRun, PartOutcome, PartRun};
+use crate::solution::{DynSolution, Solutions};
+use crate::util::time;
+
+/// How often the progress display and the timeouts are updated
+const REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Progress report of one part task, sent to the thread that collects the results
+enum Event {
+    Started,
+    Finished(Duration, Result<PartRun, Error>),
+}
+
+/// State of one part task as seen by the collecting thread
+enum Task {
+    Waiting,
+    Running(Instant),
+    Done(Duration, Result<PartRun, Error>),
+}
+
+impl Task {
+    fn status(&self) -> String {
+        match self {
+            Task::Waiting => "waiting".to_string(),
+            Task::Running(start) => format!("running {:.1}s", start.elapsed().as_secs_f64()),
+            Task::Done(_, Err(_)) => "invalid input".to_string(),
+            Task::Done(_, Ok(part_run)) => match &part_run.outcome {
+                PartOutcome::Solved(_) => format!("done in {:.1?}", part_run.solve_time),
+                PartOutcome::Panicked(_) => "PANIC".to_string(),
+                PartOutcome::TimedOut(_) => "TIMEOUT".to_string(),
+                PartOutcome::Starved => "not started".to_string(),
+            },
+        }
+    }
+}
+
+/// Parse the input and solve one part, each part task parses on its own so that both parts of
+/// a day can run at the same time
+fn run_part(
+    solution: &dyn DynSolution,
+    inputs: &Inputs,
+    day: u8,
+    part: u8,
+    source: InputSource,
+) -> (Duration, Result<PartRun, Error>) {
+    let (input, parse_time) = time(|| input::load_input(solution, inputs, day, source));
+    let part_run = input.map(|input| runner::solve_part(solution, &*input, part));
+    (parse_time, part_run)
+}
+
+/// Combine the two part tasks of a day into the result of the day
+fn day_run(inputs: &Inputs, day: u8, source: InputSource, part1: Task, part2: Task) -> DayRun {
+    let mut parse_time = Duration::ZERO;
+    let mut part_runs = Vec::new();
+    let mut error = None;
+    for task in [part1, part2] {
+        match task {
+            Task::Done(t, result) => {
+                parse_time = parse_time.max(t);
+                match result {
+                    Ok(part_run) => part_runs.push(part_run),
+                    Err(e) => error = Some(e),
+                }
+            }
+            _ => unreachable!("all tasks are finished or timed out"),
+        }
+    }
+    DayRun {
+        year: inputs.year,
+        day,
+        owner: inputs.owner.clone(),
+        input: input::source_path(inputs, day, source)
+            .display()
+            .to_string(),
+        parts: vec![1, 2],
+        parse_time,
+        result: match error {
+            Some(e) => Err(e),
+            None => Ok(part_runs),
+        },
+    }
+}
+
+/// Status line of a day in the progress display
+fn update_bar(bar: &ProgressBar, part1: &Task, part2: &Task) {
+    let status = format!("part 1: {:<16} part 2: {}", part1.status(), part2.status());
+    match (part1, part2) {
+        (Task::Done(..), Task::Done(..)) => bar.finish_with_message(status),
+        _ => bar.set_message(status),
+    }
+}
+
+/// Run both parts of all days concurrently on a thread pool while showing their progress
+///
+/// Panics are reported per part. With a `timeout`, a part that runs longer is reported as timed
+/// out. Its thread keeps running in the background until it finishes or the program exits, so
+/// once all threads of the pool are stuck on such parts, the parts still waiting for a thread
+/// are given up as well. Every call gets a pool of its own with as many threads as
+/// `RAYON_NUM_THREADS` allows, so that threads stuck in an earlier call cannot starve it.
+pub fn run_all(
+    solutions: &'static Solutions,
+    inputs: &Inputs,
+    source: InputSource,
+    timeout: Option<Duration>,
+) -> Vec<DayRun> {
+    run_all_on_threads(solutions, inputs, source, timeout, 0)
+}
+
+/// [`run_all`] on a new pool of `num_threads` threads, or rayon's default number for 0
+fn run_all_on_threads(
+    solutions: &'static Solutions,
+    inputs: &Inputs,
+    source: InputSource,
+    timeout: Option<Duration>,
+    num_threads: usize,
+) -> Vec<DayRun> {
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .expect("cannot start the threads of the pool");
+
+    // Panics are reported per part, don't mess up the progress display with the messages
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let (sender, receiver) = mpsc::channel();
+    for (i, &(day, solution)) in solutions.iter().enumerate() {
+        for part in 1..=2 {
+            let sender = sender.clone();
+            let inputs = inputs.clone();
+            let task = 2 * i + part as usize - 1;
+            pool.spawn(move || {
+                // Sending fails if the collecting thread has given up on us already
+                let _ = sender.send((task, Event::Started));
+                let (parse_time, result) = panic::catch_unwind(AssertUnwindSafe(|| {
+                    run_part(solution, &inputs, day, part, source)
+                }))
+                .unwrap_or_else(|payload| {
+                    let outcome = PartOutcome::Panicked(runner::panic_message(&*payload));
+                    (
+                        Duration::ZERO,
+                        Ok(PartRun {
+                            part,
+                            outcome,
+                            solve_time: Duration::ZERO,
+                        }),
+                    )
+                });
+                let _ = sender.send((task, Event::Finished(parse_time, result)));
+            });
+        }
+    }
+    drop(sender);
+
+    let progress = MultiProgress::new();
+    let style = ProgressStyle::with_template("{spinner} Day {prefix:>2}  {msg}").unwrap();
+    let bars: Vec<_> = solutions
+        .iter()
+        .map(|(day, _)| {
+            let bar = progress.add(ProgressBar::new_spinner().with_style(style.clone()));
+            bar.set_prefix(day.to_string());
+            bar.enable_steady_tick(REFRESH_INTERVAL);
+            bar
+        })
+        .collect();
+
+    let mut tasks: Vec<Task> = (0..2 * solutions.len()).map(|_| Task::Waiting).collect();
+    // Threads of the pool that are busy with parts that were given up
+    let mut stuck_threads = 0;
+    while tasks.iter().any(|task| !matches!(task, Task::Done(..))) {
+        match receiver.recv_timeout(REFRESH_INTERVAL) {
+            Ok((task, event)) => match (&tasks[task], event) {
+                (Task::Done(..), Event::Started) => stuck_threads += 1,
+                (Task::Done(..), Event::Finished(..)) => stuck_threads -= 1,
+                (_, Event::Started) => tasks[task] = Task::Running(Instant::now()),
+                (_, Event::Finished(parse_time, result)) => {
+                    tasks[task] = Task::Done(parse_time, result)
+                }
+            },
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                unreachable!("every task reports when it is finished")
+            }
+        }
+
+        if let Some(timeout) = timeout {
+            for (task, state) in tasks.iter_mut().enumerate() {
+                let (outcome, solve_time) = match state {
+                    Task::Running(start) if start.elapsed() >= timeout => {
+                        stuck_threads += 1;
+                        (PartOutcome::TimedOut(timeout), timeout)
+                    }
+                    Task::Waiting if stuck_threads >= pool.current_num_threads() => {
+                        (PartOutcome::Starved, Duration::ZERO)
+                    }
+                    _ => continue,
+                };
+                let part_run = PartRun {
+                    part: (task % 2) as u8 + 1,
+                    outcome,
+                    solve_time,
+                };
+                *state = Task::Done(Duration::ZERO, Ok(part_run));
+            }
+        }
+
+        for (day, bar) in bars.iter().enumerate() {
+            if !bar.is_finished() {
+                update_bar(bar, &tasks[2 * day], &tasks[2 * day + 1]);
+            }
+        }
+    }
+    progress.clear().unwrap();
+    panic::set_hook(default_hook);
+
+    let mut tasks = tasks.into_iter();
+    solutions
+        .iter()
+        .map(|&(day, _)| {
+            day_run(
+                inputs,
+                day,
+                source,
+                tasks.next().unwrap(),
+                tasks.next().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, thread};
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::error::Result;
+    use crate::input::InputVariant;
+    use crate::solution::{Answer, Solution};
+
+    /// Day whose parts take far longer than the timeout of the tests
+    struct Slow;
+
+    impl Solution for Slow {
+        type Input = ();
+
+        fn parse(_lines: &[String]) -> Result<Self::Input> {
+            Ok(())
+        }
+
+        fn part1(_input: &Self::Input) -> Answer {
+            thread::sleep(Duration::from_secs(2));
+            1.into()
+        }
+
+        fn part2(_input: &Self::Input) -> Answer {
+            thread::sleep(Duration::from_secs(2));
+            2.into()
+        }
+    }
+
+    #[test]
+    fn runs_after_timed_out_run() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("inp1_1.txt"), "").unwrap();
+        let inputs = Inputs::flat(tmp.path(), 2023);
+        let source = InputSource::File(InputVariant::Example);
+        let timeout = Some(Duration::from_millis(200));
+
+        // The single thread is stuck in the part that started first after the timeout, the other
+        // part never gets to run. The second run must not wait for the thread that is still stuck
+        // in the first one.
+        for _ in 0..2 {
+            let runs = run_all_on_threads(&[(1, &Slow)], &inputs, source, timeout, 1);
+            let mut outcomes: Vec<_> = runs[0]
+                .result
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(|part_run| match part_run.outcome {
+                    PartOutcome::TimedOut(_) => "timed out",
+                    PartOutcome::Starved => "starved",
+                    _ => "finished",
+                })
+                .collect();
+            outcomes.sort();
+            assert_eq!(outcomes, ["starved", "timed out"]);
+        }
+    }
+}
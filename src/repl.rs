@@ -0,0 +1,292 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+};
+
+use rustyline::{error::ReadlineError, DefaultEditor};
+
+use crate::input::{self, InputSource, InputVariant, Inputs};
+use crate::runner::{self, panic_message, PartOutcome};
+use crate::solution::DynSolution;
+
+/// Output of a command, or what went wrong
+pub type CommandResult = Result<String, String>;
+
+/// Parsed state of a day that can be inspected and advanced step by step in the REPL
+pub trait Explore {
+    /// Commands of the day as usage and description, for `help`
+    fn commands(&self) -> &[(&'static str, &'static str)];
+
+    /// Run `command` with `args`, or `None` if the day has no such command
+    fn run(&mut self, command: &str, args: &[&str]) -> Option<CommandResult>;
+}
+
+/// Number of steps given as the only argument of a command, 1 if there is none
+pub fn step_count(args: &[&str]) -> Result<usize, String> {
+    match args {
+        [] => Ok(1),
+        [count] => count
+            .parse()
+            .map_err(|_| format!("Expected a number of steps, not '{}'", count)),
+        _ => Err("Expected at most one argument, the number of steps".to_string()),
+    }
+}
+
+const COMMANDS: &[(&str, &str)] = &[
+    ("part <1|2>", "Solve a part with the loaded input"),
+    ("reset", "Start over from the loaded input"),
+    ("reload", "Read and parse the input file again"),
+    ("help", "Show this help"),
+    ("quit", "Leave the REPL (also exit or Ctrl-D)"),
+];
+
+/// Loaded input of a day and the state explored from it
+pub struct Repl<'a> {
+    solution: &'a dyn DynSolution,
+    inputs: &'a Inputs,
+    day: u8,
+    variant: InputVariant,
+    input: Box<dyn Any>,
+    state: Option<Box<dyn Explore>>,
+}
+
+impl<'a> Repl<'a> {
+    /// Load the input `variant` of `day`
+    pub fn new(
+        solution: &'a dyn DynSolution,
+        inputs: &'a Inputs,
+        day: u8,
+        variant: InputVariant,
+    ) -> Result<Repl<'a>, String> {
+        let input = load(solution, inputs, day, variant)?;
+        let state = solution.explore_any(&*input);
+        Ok(Repl {
+            solution,
+            inputs,
+            day,
+            variant,
+            input,
+            state,
+        })
+    }
+
+    fn help(&self) -> String {
+        let day_commands = self
+            .state
+            .as_ref()
+            .map_or(&[][..], |state| state.commands());
+        let width = COMMANDS
+            .iter()
+            .chain(day_commands)
+            .map(|(usage, _)| usage.len())
+            .max()
+            .unwrap_or(0);
+        let mut lines: Vec<String> = day_commands
+            .iter()
+            .chain(COMMANDS)
+            .map(|(usage, description)| format!("{:<width$}  {}", usage, description))
+            .collect();
+        if self.state.is_none() {
+            lines.insert(0, format!("Day {} has no commands of its own", self.day));
+        }
+        lines.join("\n")
+    }
+
+    /// Run a command line, returning its output or what went wrong
+    ///
+    /// Panics of the solver are caught and returned as errors.
+    pub fn execute(&mut self, line: &str) -> CommandResult {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = words.split_first() else {
+            return Ok(String::new());
+        };
+        match (command, args) {
+            ("help", []) => Ok(self.help()),
+            ("part", [part]) => {
+                let part = match *part {
+                    "1" => 1,
+                    "2" => 2,
+                    _ => return Err(format!("There is no part {}", part)),
+                };
+                let part_run = runner::solve_part(self.solution, &*self.input, part);
+                match &part_run.outcome {
+                    PartOutcome::Solved(answer) => Ok(format!(
+                        "{} ({:.3} ms)",
+                        answer,
+                        part_run.solve_time.as_secs_f64() * 1000.0
+                    )),
+                    _ => Err(part_run.error().unwrap()),
+                }
+            }
+            ("reset", []) => {
+                self.state = self.solution.explore_any(&*self.input);
+                Ok("Back at the start".to_string())
+            }
+            ("reload", []) => {
+                self.input = load(self.solution, self.inputs, self.day, self.variant)?;
+                self.state = self.solution.explore_any(&*self.input);
+                Ok(format!("Loaded {}", self.variant.file_name(self.day)))
+            }
+            _ => {
+                let result = match &mut self.state {
+                    Some(state) => {
+                        panic::catch_unwind(AssertUnwindSafe(|| state.run(command, args)))
+                            .unwrap_or_else(|payload| {
+                                Some(Err(format!("panicked: {}", panic_message(&*payload))))
+                            })
+                    }
+                    None => None,
+                };
+                result.unwrap_or_else(|| {
+                    Err(format!(
+                        "Unknown command or arguments '{}', see help",
+                        line.trim()
+                    ))
+                })
+            }
+        }
+    }
+}
+
+/// Read and parse an input, catching a panic of the parser
+fn load(
+    solution: &dyn DynSolution,
+    inputs: &Inputs,
+    day: u8,
+    variant: InputVariant,
+) -> Result<Box<dyn Any>, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        input::load_input(solution, inputs, day, InputSource::File(variant))
+    }))
+    .map_err(|payload| format!("panicked while parsing: {}", panic_message(&*payload)))?
+    .map_err(|e| e.to_string())
+}
+
+/// Load the input `variant` of `day` and read commands from the terminal until quit
+pub fn repl(
+    solution: &dyn DynSolution,
+    inputs: &Inputs,
+    day: u8,
+    variant: InputVariant,
+) -> Result<(), String> {
+    let mut repl = Repl::new(solution, inputs, day, variant)?;
+    let mut editor = DefaultEditor::new().map_err(|e| format!("Cannot use terminal: {}", e))?;
+    let prompt = format!("day{}> ", day);
+    println!(
+        "Loaded {}, type help for the commands",
+        variant.file_name(day)
+    );
+
+    // Panics are shown as the error of the command, don't clutter the output with the default
+    // message
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = loop {
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            // Ctrl-C only discards the line
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break Ok(()),
+            Err(e) => break Err(format!("Cannot read command: {}", e)),
+        };
+        if matches!(line.trim(), "quit" | "exit") {
+            break Ok(());
+        }
+        if !line.trim().is_empty() {
+            // Only fails if there is no history
+            let _ = editor.add_history_entry(line.as_str());
+        }
+        match repl.execute(&line) {
+            Ok(output) if output.is_empty() => {}
+            Ok(output) => println!("{}", output),
+            Err(e) => println!("ERROR: {}", e),
+        }
+    };
+    panic::set_hook(default_hook);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::solution::{self, Answer, Solution};
+    use crate::SOLUTIONS;
+
+    /// Day without commands of its own that panics in part 2
+    struct Counting;
+
+    impl Solution for Counting {
+        type Input = usize;
+
+        fn parse(lines: &[String]) -> crate::error::Result<usize> {
+            Ok(lines.len())
+        }
+
+        fn part1(input: &usize) -> Answer {
+            (*input).into()
+        }
+
+        fn part2(_input: &usize) -> Answer {
+            panic!("not solved yet")
+        }
+    }
+
+    fn inputs() -> Inputs {
+        Inputs::flat(Path::new(env!("CARGO_MANIFEST_DIR")), 2023)
+    }
+
+    #[test]
+    fn builtin_commands() {
+        let inputs = inputs();
+        let mut repl = Repl::new(&Counting, &inputs, 1, InputVariant::Example).unwrap();
+        assert_eq!(repl.execute("  "), Ok(String::new()));
+        let lines = crate::util::file_lines(inputs.input_path(1, InputVariant::Example)).unwrap();
+        assert!(repl
+            .execute("part 1")
+            .unwrap()
+            .starts_with(&format!("{} (", lines.len())));
+        assert_eq!(
+            repl.execute("part 2"),
+            Err("panicked: not solved yet".to_string())
+        );
+        assert!(repl.execute("part 3").is_err());
+        assert_eq!(repl.execute("reload"), Ok("Loaded inp1_1.txt".to_string()));
+        assert!(repl
+            .execute("help")
+            .unwrap()
+            .contains("no commands of its own"));
+        assert_eq!(
+            repl.execute("grid"),
+            Err("Unknown command or arguments 'grid', see help".to_string())
+        );
+    }
+
+    #[test]
+    fn day_commands() {
+        let inputs = inputs();
+        let mut repl = Repl::new(
+            solution::find(SOLUTIONS, 14).unwrap(),
+            &inputs,
+            14,
+            InputVariant::Example,
+        )
+        .unwrap();
+        assert!(repl.execute("help").unwrap().contains("step [N]"));
+        let start = repl.execute("grid").unwrap();
+        repl.execute("step").unwrap();
+        assert_ne!(repl.execute("grid").unwrap(), start);
+        repl.execute("reset").unwrap();
+        assert_eq!(repl.execute("grid").unwrap(), start);
+        assert!(repl.execute("step many").is_err());
+    }
+
+    #[test]
+    fn step_counts() {
+        assert_eq!(step_count(&[]), Ok(1));
+        assert_eq!(step_count(&["10"]), Ok(10));
+        assert!(step_count(&["-1"]).is_err());
+        assert!(step_count(&["1", "2"]).is_err());
+    }
+}
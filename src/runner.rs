@@ -0,0 +1,280 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    time::Duration,
+};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::input::{self, InputSource, Inputs};
+use crate::parallel;
+use crate::solution::{self, Answer, DynSolution, Solutions};
+use crate::util::time;
+
+/// How `run` prints its results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// One line per answer, errors on stderr
+    Human,
+    /// One JSON object per line for every day and part
+    Json,
+}
+
+pub enum PartOutcome {
+    Solved(Answer),
+    Panicked(String),
+    /// Gave up waiting for the solver after the given time
+    TimedOut(Duration),
+    /// Never started since all threads were busy with timed out parts
+    Starved,
+}
+
+pub struct PartRun {
+    pub part: u8,
+    pub outcome: PartOutcome,
+    pub solve_time: Duration,
+}
+
+impl PartRun {
+    /// Description of the failure, if the part was not solved
+    pub fn error(&self) -> Option<String> {
+        match &self.outcome {
+            PartOutcome::Solved(_) => None,
+            PartOutcome::Panicked(msg) => Some(format!("panicked: {}", msg)),
+            PartOutcome::TimedOut(t) => Some(format!("timed out after {:.1}s", t.as_secs_f64())),
+            PartOutcome::Starved => {
+                Some("not started, all threads are stuck on timed out parts".into())
+            }
+        }
+    }
+}
+
+/// Results of running the solver of one day
+pub struct DayRun {
+    pub year: u16,
+    pub day: u8,
+    /// Owner of the input in the tree layout
+    pub owner: Option<String>,
+    /// Input file name, or `<stdin>`
+    pub input: String,
+    /// Parts that were requested, also if parsing failed
+    pub parts: Vec<u8>,
+    /// Time to read and parse the input
+    pub parse_time: Duration,
+    /// Answers of all `parts`, or the parse error
+    pub result: Result<Vec<PartRun>, Error>,
+}
+
+/// Line of the JSON output: the result of one part of one day
+///
+/// Answers are strings, since they may not fit into a JSON number without losing precision.
+#[derive(Serialize)]
+struct PartRecord<'a> {
+    year: u16,
+    day: u8,
+    part: u8,
+    owner: Option<&'a str>,
+    input: &'a str,
+    answer: Option<String>,
+    parse_ms: f64,
+    solve_ms: Option<f64>,
+    error: Option<String>,
+}
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Solve one part of a parsed input, catching a panic of the solver
+pub fn solve_part(solution: &dyn DynSolution, input: &dyn Any, part: u8) -> PartRun {
+    let (answer, solve_time) = time(|| {
+        panic::catch_unwind(AssertUnwindSafe(|| match part {
+            1 => solution.part1_any(input),
+            _ => solution.part2_any(input),
+        }))
+    });
+    PartRun {
+        part,
+        outcome: match answer {
+            Ok(answer) => PartOutcome::Solved(answer),
+            Err(payload) => PartOutcome::Panicked(panic_message(&*payload)),
+        },
+        solve_time,
+    }
+}
+
+/// Days that `run` solves
+pub enum DaySelection {
+    /// One day, only the given part if there is one
+    One(u8, Option<u8>),
+    /// All days in order
+    All,
+    /// All parts of all days at the same time, giving up on parts after the timeout if given
+    Parallel(Option<Duration>),
+}
+
+/// Run the selected days on each of `all_inputs` and print the results
+///
+/// Returns whether all parts were solved.
+pub fn run(
+    solutions: &'static Solutions,
+    all_inputs: &[Inputs],
+    selection: &DaySelection,
+    source: InputSource,
+    format: OutputFormat,
+) -> bool {
+    let mut ok = true;
+    let mut report = |day_run: DayRun| {
+        day_run.print(format);
+        ok &= day_run.is_ok();
+    };
+    for inputs in all_inputs {
+        match *selection {
+            DaySelection::One(day, part) => match solution::find(solutions, day) {
+                Ok(solution) => report(run_day(solution, inputs, day, part, source)),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return false;
+                }
+            },
+            // Print every day as soon as it is done
+            DaySelection::All => {
+                for &(day, solution) in solutions {
+                    report(run_day(solution, inputs, day, None, source));
+                }
+            }
+            DaySelection::Parallel(timeout) => {
+                parallel::run_all(solutions, inputs, source, timeout)
+                    .into_iter()
+                    .for_each(&mut report);
+            }
+        }
+    }
+    ok
+}
+
+/// Read and parse the input of `day`, then solve `part`, or both parts if not given
+pub fn run_day(
+    solution: &dyn DynSolution,
+    inputs: &Inputs,
+    day: u8,
+    part: Option<u8>,
+    source: InputSource,
+) -> DayRun {
+    let parts: Vec<u8> = (1..=2)
+        .filter(|p| part.is_none() || part == Some(*p))
+        .collect();
+    let (input, parse_time) = time(|| input::load_input(solution, inputs, day, source));
+    let result = input.map(|input| {
+        parts
+            .iter()
+            .map(|&part| solve_part(solution, &*input, part))
+            .collect()
+    });
+    DayRun {
+        year: inputs.year,
+        day,
+        owner: inputs.owner.clone(),
+        input: input::source_path(inputs, day, source)
+            .display()
+            .to_string(),
+        parts,
+        parse_time,
+        result,
+    }
+}
+
+impl DayRun {
+    /// Whether all parts were solved
+    pub fn is_ok(&self) -> bool {
+        self.result.as_ref().is_ok_and(|part_runs| {
+            part_runs
+                .iter()
+                .all(|p| matches!(p.outcome, PartOutcome::Solved(_)))
+        })
+    }
+
+    fn records(&self) -> Vec<PartRecord<'_>> {
+        let record = |part| PartRecord {
+            year: self.year,
+            day: self.day,
+            part,
+            owner: self.owner.as_deref(),
+            input: &self.input,
+            answer: None,
+            parse_ms: ms(self.parse_time),
+            solve_ms: None,
+            error: None,
+        };
+        match &self.result {
+            Ok(part_runs) => part_runs
+                .iter()
+                .map(|p| PartRecord {
+                    answer: match &p.outcome {
+                        PartOutcome::Solved(answer) => Some(answer.to_string()),
+                        _ => None,
+                    },
+                    solve_ms: Some(ms(p.solve_time)),
+                    error: p.error(),
+                    ..record(p.part)
+                })
+                .collect(),
+            Err(e) => self
+                .parts
+                .iter()
+                .map(|&part| PartRecord {
+                    error: Some(e.to_string()),
+                    ..record(part)
+                })
+                .collect(),
+        }
+    }
+
+    /// "Day N", followed by the owner of the input if there is one
+    fn label(&self) -> String {
+        match &self.owner {
+            Some(owner) => format!("Day {} [{}]", self.day, owner),
+            None => format!("Day {}", self.day),
+        }
+    }
+
+    pub fn print(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Human => match &self.result {
+                Ok(part_runs) => {
+                    for p in part_runs {
+                        match &p.outcome {
+                            PartOutcome::Solved(answer) => {
+                                println!("{} part {}: {}", self.label(), p.part, answer)
+                            }
+                            _ => eprintln!(
+                                "{} part {}: {}",
+                                self.label(),
+                                p.part,
+                                p.error().unwrap()
+                            ),
+                        }
+                    }
+                }
+                Err(e) => eprintln!("{}: {}", self.label(), e),
+            },
+            OutputFormat::Json => {
+                for record in self.records() {
+                    println!("{}", serde_json::to_string(&record).unwrap());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::input::{InputVariant, Inputs};
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))
+}
+
+fn write(path: &Path, text: &str) -> Result<(), String> {
+    fs::write(path, text).map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+}
+
+/// Day number of a `(N, &dayN::DayN),` line in the `SOLUTIONS` array
+fn registered_day(line: &str) -> Option<u8> {
+    line.trim()
+        .strip_prefix('(')?
+        .split_once(',')?
+        .0
+        .parse()
+        .ok()
+}
+
+/// Module name of a `mod name;` or `pub mod name;` line
+fn declared_mod(line: &str) -> Option<&str> {
+    let line = line.strip_prefix("pub ").unwrap_or(line);
+    line.strip_prefix("mod ")?.strip_suffix(';')
+}
+
+/// Add `pub mod dayN;` and the solver entry of `day` to the source of `lib.rs`
+///
+/// The module goes into the block of `mod` declarations in the order rustfmt keeps them, the
+/// solver goes into `SOLUTIONS` sorted by day.
+fn register_day(lib_rs: &str, day: u8) -> Result<String, String> {
+    let mod_name = format!("day{}", day);
+    let mut lines: Vec<String> = lib_rs.lines().map(|l| l.to_string()).collect();
+    if lines
+        .iter()
+        .any(|l| declared_mod(l) == Some(mod_name.as_str()))
+    {
+        return Err(format!("Day {} is already registered in lib.rs", day));
+    }
+
+    let mod_pos = lines
+        .iter()
+        .position(|l| declared_mod(l).is_some_and(|m| m > mod_name.as_str()))
+        .or_else(|| {
+            lines
+                .iter()
+                .rposition(|l| declared_mod(l).is_some())
+                .map(|p| p + 1)
+        })
+        .ok_or("No module declarations found in lib.rs")?;
+    lines.insert(mod_pos, format!("pub mod {};", mod_name));
+
+    let array_start = lines
+        .iter()
+        .position(|l| l.starts_with("pub const SOLUTIONS"))
+        .ok_or("No SOLUTIONS array found in lib.rs")?;
+    let array_end = array_start
+        + lines[array_start..]
+            .iter()
+            .position(|l| l == "];")
+            .ok_or("SOLUTIONS array in lib.rs is not terminated by '];'")?;
+    let entry_pos = (array_start + 1..array_end)
+        .find(|&i| registered_day(&lines[i]).is_some_and(|d| d > day))
+        .unwrap_or(array_end);
+    lines.insert(
+        entry_pos,
+        format!("    ({}, &day{}::Day{}),", day, day, day),
+    );
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Answer tables for `day` with the parts commented out, to be filled in once the answers are
+/// known
+///
+/// Until then `verify` reports the answers as missing.
+fn answers_entry(day: u8) -> String {
+    [InputVariant::Example, InputVariant::Real]
+        .iter()
+        .map(|variant| format!("\n[day{}.{}]\n# part1 =\n# part2 =\n", day, variant))
+        .collect()
+}
+
+/// Create the module of `day` from `src/template.rs`, register it in `lib.rs` and add empty
+/// input files and answers entries
+///
+/// In the tree layout the example goes into the directory shared by all owners of the year.
+///
+/// Refuses to touch anything if the day already exists. All days of 2023 are solved, so this is
+/// for a copy of the crate started for another year, with `YEAR` changed and `SOLUTIONS`
+/// emptied. Returns the created or modified files.
+pub fn new_day(
+    crate_dir: &Path,
+    inputs: &Inputs,
+    answers_path: &Path,
+    day: u8,
+) -> Result<Vec<PathBuf>, String> {
+    let src_dir = crate_dir.join("src");
+    let day_path = src_dir.join(format!("day{}.rs", day));
+    let lib_path = src_dir.join("lib.rs");
+    if day_path.exists() {
+        return Err(format!(
+            "Day {} already exists at {}",
+            day,
+            day_path.display()
+        ));
+    }
+
+    // Prepare everything before writing, so that a failure leaves no half-created day
+    let day_source = read(&src_dir.join("template.rs"))?.replace("DayN", &format!("Day{}", day));
+    let lib_source = register_day(&read(&lib_path)?, day)?;
+    let answers = match answers_path.exists() {
+        true => read(answers_path)?,
+        false => String::new(),
+    };
+    let has_answers = answers.lines().any(|l| {
+        l.starts_with(&format!("[day{}.", day)) || l.starts_with(&format!("# [day{}.", day))
+    });
+
+    let mut changed = Vec::new();
+    write(&day_path, &day_source)?;
+    changed.push(day_path);
+    write(&lib_path, &lib_source)?;
+    changed.push(lib_path);
+
+    for variant in [InputVariant::Example, InputVariant::Real] {
+        if inputs.input_path(day, variant).exists() {
+            continue;
+        }
+        let dir = match variant {
+            InputVariant::Example => inputs.shared_dir(),
+            _ => &inputs.dirs()[0],
+        };
+        fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+        let path = dir.join(variant.file_name(day));
+        write(&path, "")?;
+        changed.push(path);
+    }
+
+    if !has_answers {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(answers_path)
+            .map_err(|e| format!("Cannot write {}: {}", answers_path.display(), e))?;
+        file.write_all(answers_entry(day).as_bytes())
+            .map_err(|e| format!("Cannot write {}: {}", answers_path.display(), e))?;
+        changed.push(answers_path.to_path_buf());
+    }
+
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::answers::Answers;
+    use crate::input::ANSWERS_FILE;
+    use crate::solution::{Answer, Solution};
+    use crate::template::DayN;
+
+    const LIB_RS: &str = "\
+use solution::Solutions;
+
+pub mod day1;
+pub mod day10;
+pub mod day2;
+pub mod day4;
+pub mod error;
+
+pub const SOLUTIONS: &Solutions = &[
+    (1, &day1::Day1),
+    (2, &day2::Day2),
+    (4, &day4::Day4),
+    (10, &day10::Day10),
+];
+";
+
+    /// Crate directory with the sample `lib.rs` and the real template
+    fn crate_dir() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        let src_dir = tmp.path().join("src");
+        fs::create_dir(&src_dir).unwrap();
+        fs::write(src_dir.join("lib.rs"), LIB_RS).unwrap();
+        fs::write(src_dir.join("template.rs"), include_str!("template.rs")).unwrap();
+        tmp
+    }
+
+    #[test]
+    fn registers_in_order() {
+        let lib_rs = register_day(LIB_RS, 3).unwrap();
+        let lines: Vec<_> = lib_rs.lines().collect();
+
+        // rustfmt sorts the modules as strings, "day3" after "day2"
+        let mods: Vec<_> = lines.iter().filter_map(|l| declared_mod(l)).collect();
+        assert_eq!(mods, ["day1", "day10", "day2", "day3", "day4", "error"]);
+        // The entries stay sorted numerically, with gaps for the days not solved yet
+        let days: Vec<_> = lines.iter().filter_map(|l| registered_day(l)).collect();
+        assert_eq!(days, [1, 2, 3, 4, 10]);
+
+        let lib_rs = register_day(&lib_rs, 11).unwrap();
+        let days: Vec<_> = lib_rs.lines().filter_map(registered_day).collect();
+        assert_eq!(days, [1, 2, 3, 4, 10, 11]);
+        assert!(lib_rs.contains("pub mod day11;\npub mod day2;"));
+        assert!(lib_rs.contains("    (11, &day11::Day11),\n];"));
+    }
+
+    #[test]
+    fn refuses_registered_day() {
+        assert!(register_day(LIB_RS, 4).is_err());
+    }
+
+    #[test]
+    fn creates_day() {
+        let tmp = crate_dir();
+        let inputs = Inputs::flat(tmp.path(), 2023);
+        let answers_path = tmp.path().join(ANSWERS_FILE);
+
+        let changed = new_day(tmp.path(), &inputs, &answers_path, 3).unwrap();
+
+        let src_dir = tmp.path().join("src");
+        assert_eq!(
+            changed,
+            [
+                src_dir.join("day3.rs"),
+                src_dir.join("lib.rs"),
+                tmp.path().join("inp3_1.txt"),
+                tmp.path().join("inp3_2.txt"),
+                answers_path.clone(),
+            ]
+        );
+        assert_eq!(
+            read(&src_dir.join("day3.rs")).unwrap(),
+            include_str!("template.rs").replace("DayN", "Day3")
+        );
+        assert_eq!(
+            read(&src_dir.join("lib.rs")).unwrap(),
+            register_day(LIB_RS, 3).unwrap()
+        );
+        assert_eq!(read(&tmp.path().join("inp3_1.txt")).unwrap(), "");
+
+        // The new tables parse, with both parts still missing rather than skipped
+        let answers = Answers::load(&answers_path).unwrap();
+        assert!(answers.applies(3, InputVariant::Example, 1));
+        assert_eq!(answers.expected(3, InputVariant::Real, 2), None);
+    }
+
+    /// The template is compiled as a module in tests, so that what `new` copies is a working
+    /// solver
+    #[test]
+    fn template_solves() {
+        let lines = vec!["a".to_string(), "b".to_string()];
+        let input = DayN::parse(&lines).unwrap();
+        assert_eq!(DayN::part1(&input), Answer::Int(0));
+        assert_eq!(DayN::part2(&input), Answer::Int(0));
+        assert!(DayN::parse(&["".to_string()]).is_err());
+    }
+
+    #[test]
+    fn refuses_to_overwrite() {
+        let tmp = crate_dir();
+        let inputs = Inputs::flat(tmp.path(), 2023);
+        let answers_path = tmp.path().join(ANSWERS_FILE);
+        new_day(tmp.path(), &inputs, &answers_path, 3).unwrap();
+        let day_path = tmp.path().join("src").join("day3.rs");
+        fs::write(&day_path, "solved").unwrap();
+        let lib_rs = read(&tmp.path().join("src").join("lib.rs")).unwrap();
+
+        assert!(new_day(tmp.path(), &inputs, &answers_path, 3).is_err());
+        assert_eq!(read(&day_path).unwrap(), "solved");
+        assert_eq!(
+            read(&tmp.path().join("src").join("lib.rs")).unwrap(),
+            lib_rs
+        );
+    }
+
+    #[test]
+    fn keeps_existing_inputs_and_answers() {
+        let tmp = crate_dir();
+        let inputs = Inputs::flat(tmp.path(), 2023);
+        let answers_path = tmp.path().join(ANSWERS_FILE);
+        fs::write(tmp.path().join("inp3_1.txt"), "example").unwrap();
+        fs::write(&answers_path, "[day3.example]\npart1 = 1\n").unwrap();
+
+        let changed = new_day(tmp.path(), &inputs, &answers_path, 3).unwrap();
+
+        assert_eq!(changed.len(), 3);
+        assert_eq!(read(&tmp.path().join("inp3_1.txt")).unwrap(), "example");
+        assert_eq!(read(&answers_path).unwrap(), "[day3.example]\npart1 = 1\n");
+    }
+}
@@ -0,0 +1,138 @@
+use std::any::Any;
+use std::fmt;
+
+use num::BigInt;
+
+use crate::error::Result;
+use crate::repl::Explore;
+
+/// Answer to one part of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    BigInt(BigInt),
+    Str(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(i) => write!(f, "{}", i),
+            Answer::BigInt(i) => write!(f, "{}", i),
+            Answer::Str(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! answer_from_small_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(val: $t) -> Self {
+                Answer::Int(val.into())
+            }
+        })*
+    };
+}
+answer_from_small_int!(i8, u8, i16, u16, i32, u32, i64);
+
+macro_rules! answer_from_large_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(val: $t) -> Self {
+                match i64::try_from(val) {
+                    Ok(i) => Answer::Int(i),
+                    Err(_) => Answer::BigInt(val.into()),
+                }
+            }
+        })*
+    };
+}
+answer_from_large_int!(u64, usize, isize, i128, u128);
+
+impl From<BigInt> for Answer {
+    fn from(val: BigInt) -> Self {
+        Answer::BigInt(val)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(val: String) -> Self {
+        Answer::Str(val)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(val: &str) -> Self {
+        Answer::Str(val.to_string())
+    }
+}
+
+/// Solver for the puzzle of one day: parse the input once, then answer both parts from it
+pub trait Solution {
+    type Input;
+
+    fn parse(lines: &[String]) -> Result<Self::Input>;
+    fn part1(input: &Self::Input) -> Answer;
+    fn part2(input: &Self::Input) -> Answer;
+
+    /// State to inspect and advance step by step in the REPL, for days that have any
+    fn explore(_input: &Self::Input) -> Option<Box<dyn Explore>> {
+        None
+    }
+}
+
+/// Object-safe view of a [`Solution`] so that all days can be handled uniformly
+pub trait DynSolution: Sync {
+    fn parse_any(&self, lines: &[String]) -> Result<Box<dyn Any>>;
+    fn part1_any(&self, input: &dyn Any) -> Answer;
+    fn part2_any(&self, input: &dyn Any) -> Answer;
+    fn explore_any(&self, input: &dyn Any) -> Option<Box<dyn Explore>>;
+}
+
+fn downcast_input<S: Solution>(input: &dyn Any) -> &S::Input
+where
+    S::Input: 'static,
+{
+    input
+        .downcast_ref()
+        .expect("input was not parsed by this solution")
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync,
+    S::Input: 'static,
+{
+    fn parse_any(&self, lines: &[String]) -> Result<Box<dyn Any>> {
+        Ok(Box::new(S::parse(lines)?))
+    }
+
+    fn part1_any(&self, input: &dyn Any) -> Answer {
+        S::part1(downcast_input::<S>(input))
+    }
+
+    fn part2_any(&self, input: &dyn Any) -> Answer {
+        S::part2(downcast_input::<S>(input))
+    }
+
+    fn explore_any(&self, input: &dyn Any) -> Option<Box<dyn Explore>> {
+        S::explore(downcast_input::<S>(input))
+    }
+}
+
+/// Solvers of a year, each with the day it solves, sorted by day
+///
+/// Days without a solver are left out, so the days are not necessarily contiguous.
+pub type Solutions = [(u8, &'static dyn DynSolution)];
+
+/// Solver of `day`, or an error if there is none
+pub fn find(
+    solutions: &Solutions,
+    day: u8,
+) -> std::result::Result<&'static dyn DynSolution, String> {
+    solutions
+        .iter()
+        .find(|(d, _)| *d == day)
+        .map(|&(_, solution)| solution)
+        .ok_or_else(|| format!("Day {} has no solver", day))
+}
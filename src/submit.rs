@@ -0,0 +1,507 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use num::BigInt;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::fetch::Account;
+use crate::http::Http;
+use crate::input::{InputSource, InputVariant, Inputs};
+use crate::runner::{self, PartOutcome};
+use crate::solution::DynSolution;
+
+/// What the website said about a submitted answer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong without a hint in which direction
+    Wrong,
+    /// Submitted too soon after the previous answer, the answer was not checked
+    RateLimited,
+    /// The part is solved already or not unlocked yet
+    WrongLevel,
+    /// Response text that is not understood
+    Unknown(String),
+}
+
+impl Verdict {
+    /// Whether the answer was checked and found wrong
+    fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "wrong, too high"),
+            Verdict::TooLow => write!(f, "wrong, too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::RateLimited => write!(f, "not checked, submitted too soon"),
+            Verdict::WrongLevel => write!(f, "not checked, part is solved already or locked"),
+            Verdict::Unknown(text) => write!(f, "unknown response: {}", text),
+        }
+    }
+}
+
+/// Text of the `<article>` of a response page without markup
+fn article_text(body: &str) -> String {
+    let article = match (body.find("<article>"), body.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &body[start + "<article>".len()..end],
+        _ => body,
+    };
+    let text = Regex::new("<[^>]*>").unwrap().replace_all(article, "");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Seconds the website asks to wait before the next submission, if it says so
+fn wait_seconds(text: &str) -> Option<u64> {
+    let left = Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait").unwrap();
+    if let Some(caps) = left.captures(text) {
+        let minutes: u64 = match caps.get(1) {
+            Some(m) => m.as_str().parse().ok()?,
+            None => 0,
+        };
+        let seconds: u64 = caps[2].parse().ok()?;
+        return minutes.checked_mul(60)?.checked_add(seconds);
+    }
+    let wrong = Regex::new(r"(?i)please wait (one|\d+) minutes? before trying again").unwrap();
+    let caps = wrong.captures(text)?;
+    let minutes: u64 = match &caps[1] {
+        "one" => 1,
+        n => n.parse().ok()?,
+    };
+    minutes.checked_mul(60)
+}
+
+/// Verdict and requested wait in seconds from the response page to a submitted answer
+pub fn parse_response(body: &str) -> (Verdict, Option<u64>) {
+    let text = article_text(body);
+    let verdict = if text.contains("That's the right answer") {
+        Verdict::Correct
+    } else if text.contains("That's not the right answer") {
+        if text.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if text.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else if text.contains("You gave an answer too recently") {
+        Verdict::RateLimited
+    } else if text.contains("You don't seem to be solving the right level") {
+        Verdict::WrongLevel
+    } else {
+        Verdict::Unknown(text.clone())
+    };
+    (verdict, wait_seconds(&text))
+}
+
+/// An answer that was checked by the website
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submission {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+    /// Unix time of the submission in seconds
+    pub time: u64,
+}
+
+/// Answers submitted so far, to avoid submissions that are known to be wrong or too early
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    submissions: Vec<Submission>,
+    /// Unix time in seconds before which the website does not take answers
+    wait_until: Option<u64>,
+}
+
+/// File with the submission history, next to the cached inputs of the user
+pub fn history_path(cache_dir: &Path) -> PathBuf {
+    cache_dir.join("submissions.json")
+}
+
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+impl History {
+    /// Load the history, or start an empty one if the file does not exist yet
+    pub fn load(path: &Path) -> Result<History, String> {
+        if !path.exists() {
+            return Ok(History::default());
+        }
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid submission history {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+        }
+        let text = serde_json::to_string_pretty(self).unwrap();
+        fs::write(path, text).map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+    }
+
+    fn submissions(&self, day: u8, part: u8) -> impl Iterator<Item = &Submission> {
+        self.submissions
+            .iter()
+            .filter(move |s| s.day == day && s.part == part)
+    }
+
+    /// Why `answer` must not be submitted for `day` and `part` at time `now`, if anything
+    /// speaks against it
+    ///
+    /// Refuses answers of solved parts, answers that were wrong before, numbers outside of
+    /// the bounds given by earlier "too high" and "too low" answers and submissions before the
+    /// wait requested by the website is over.
+    pub fn check(&self, day: u8, part: u8, answer: &str, now: u64) -> Result<(), String> {
+        if let Some(correct) = self
+            .submissions(day, part)
+            .find(|s| s.verdict == Verdict::Correct)
+        {
+            return Err(format!(
+                "Day {} part {} is solved already, the answer was {}",
+                day, part, correct.answer
+            ));
+        }
+        if let Some(previous) = self.submissions(day, part).find(|s| s.answer == answer) {
+            return Err(format!(
+                "{} was submitted before and is {}",
+                answer, previous.verdict
+            ));
+        }
+        if let Ok(number) = answer.parse::<BigInt>() {
+            let bound = |verdict: Verdict| {
+                self.submissions(day, part)
+                    .filter(move |s| s.verdict == verdict)
+                    .filter_map(|s| s.answer.parse::<BigInt>().ok())
+            };
+            if let Some(high) = bound(Verdict::TooHigh).min().filter(|high| number >= *high) {
+                return Err(format!(
+                    "{} is too high, {} was too high already",
+                    answer, high
+                ));
+            }
+            if let Some(low) = bound(Verdict::TooLow).max().filter(|low| number <= *low) {
+                return Err(format!(
+                    "{} is too low, {} was too low already",
+                    answer, low
+                ));
+            }
+        }
+        match self.wait_until {
+            Some(until) if until > now => Err(format!(
+                "The website asked to wait {}s more before submitting again",
+                until - now
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Remember the response to a submitted answer
+    pub fn record(
+        &mut self,
+        day: u8,
+        part: u8,
+        answer: &str,
+        verdict: &Verdict,
+        wait: Option<u64>,
+        now: u64,
+    ) {
+        if let Some(wait) = wait {
+            self.wait_until = Some(now.saturating_add(wait));
+        }
+        if *verdict == Verdict::Correct || verdict.is_wrong() {
+            self.submissions.push(Submission {
+                day,
+                part,
+                answer: answer.to_string(),
+                verdict: verdict.clone(),
+                time: now,
+            });
+        }
+    }
+}
+
+/// Submit `answer` for `day` and `part` unless the history at `history_path` rules it out,
+/// and record the verdict there
+///
+/// `session` is only asked for if the answer is actually sent.
+#[allow(clippy::too_many_arguments)]
+pub fn submit_answer(
+    http: &dyn Http,
+    history_path: &Path,
+    year: u16,
+    day: u8,
+    part: u8,
+    answer: &str,
+    now: u64,
+    session: impl FnOnce() -> Result<String, String>,
+) -> Result<Verdict, String> {
+    let mut history = History::load(history_path)?;
+    history.check(day, part, answer, now)?;
+
+    let path = format!("/{}/day/{}/answer", year, day);
+    let level = part.to_string();
+    let response = http.post(&path, &session()?, &[("level", &level), ("answer", answer)])?;
+    if response.status != 200 {
+        return Err(format!(
+            "Unexpected response {} for {}: {}",
+            response.status,
+            path,
+            response.body.lines().next().unwrap_or("").trim()
+        ));
+    }
+
+    let (verdict, wait) = parse_response(&response.body);
+    history.record(day, part, answer, &verdict, wait, now);
+    history.save(history_path)?;
+    Ok(verdict)
+}
+
+/// Solve `part` of `day` with the real input and submit the answer with `account`
+///
+/// Prints the answer before submitting it and the verdict afterwards.
+pub fn solve_and_submit(
+    http: &dyn Http,
+    solution: &dyn DynSolution,
+    inputs: &Inputs,
+    account: &Account,
+    day: u8,
+    part: u8,
+) -> Result<Verdict, String> {
+    let source = InputSource::File(InputVariant::Real);
+    let day_run = runner::run_day(solution, inputs, day, Some(part), source);
+    let part_run = match &day_run.result {
+        Ok(part_runs) => &part_runs[0],
+        Err(e) => return Err(format!("Day {}: {}", day, e)),
+    };
+    let answer = match &part_run.outcome {
+        PartOutcome::Solved(answer) => answer.to_string(),
+        _ => {
+            return Err(format!(
+                "Day {} part {}: {}",
+                day,
+                part,
+                part_run.error().unwrap()
+            ))
+        }
+    };
+    println!("Day {} part {}: {}", day, part, answer);
+
+    let cache_dir = account.cache_dir(inputs.year, inputs.owner.as_deref())?;
+    let verdict = submit_answer(
+        http,
+        &history_path(&cache_dir),
+        inputs.year,
+        day,
+        part,
+        &answer,
+        unix_time(),
+        || account.session(),
+    )?;
+    println!("{}", verdict);
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::http::{FakeHttp, Request};
+
+    fn page(article: &str) -> String {
+        format!(
+            "<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>",
+            article
+        )
+    }
+
+    fn session() -> Result<String, String> {
+        Ok("abc".to_string())
+    }
+
+    const CORRECT: &str = "That's the right answer! You are <span class=\"day-success\">one gold \
+        star</span> closer to restoring snow operations. <a href=\"/2023/day/7#part2\">[Continue \
+        to Part Two]</a>";
+    const TOO_HIGH: &str = "That's not the right answer; your answer is too high.  If you're \
+        stuck, make sure you're using the full input data; there are also some general tips on \
+        the <a href=\"/2023/about\">about page</a>, or you can ask for hints on the \
+        <a href=\"https://www.reddit.com/r/adventofcode/\" target=\"_blank\">subreddit</a>.  \
+        Please wait one minute before trying again. <a href=\"/2023/day/7\">[Return to Day 7]</a>";
+    const TOO_LOW: &str = "That's not the right answer; your answer is too low.  Because you \
+        have guessed incorrectly 4 times on this puzzle, please wait 5 minutes before trying \
+        again. <a href=\"/2023/day/7\">[Return to Day 7]</a>";
+    const TOO_RECENT: &str = "You gave an answer too recently; you have to wait after \
+        submitting an answer before trying again.  You have 1m 12s left to wait. \
+        <a href=\"/2023/day/7\">[Return to Day 7]</a>";
+    /// Wait of exactly `u64::MAX` seconds
+    const HUGE_WAIT: &str = "You gave an answer too recently; you have to wait after \
+        submitting an answer before trying again.  You have 307445734561825860m 15s left to wait.";
+    const WRONG_LEVEL: &str = "You don't seem to be solving the right level.  Did you already \
+        complete it? <a href=\"/2023/day/7\">[Return to Day 7]</a>";
+
+    #[test]
+    fn parses_responses() {
+        assert_eq!(parse_response(&page(CORRECT)), (Verdict::Correct, None));
+        assert_eq!(
+            parse_response(&page(TOO_HIGH)),
+            (Verdict::TooHigh, Some(60))
+        );
+        assert_eq!(parse_response(&page(TOO_LOW)), (Verdict::TooLow, Some(300)));
+        assert_eq!(
+            parse_response(&page(TOO_RECENT)),
+            (Verdict::RateLimited, Some(72))
+        );
+        assert_eq!(
+            parse_response(&page(WRONG_LEVEL)),
+            (Verdict::WrongLevel, None)
+        );
+        assert_eq!(
+            parse_response(&page("Something <em>else</em>")),
+            (Verdict::Unknown("Something else".to_string()), None)
+        );
+        assert_eq!(
+            parse_response(&page(HUGE_WAIT)),
+            (Verdict::RateLimited, Some(u64::MAX))
+        );
+        // A wait that does not fit into u64 is not known
+        assert_eq!(
+            parse_response(&page(
+                "You gave an answer too recently; you have to wait after \
+                submitting an answer before trying again.  You have 99999999999999999999m 1s \
+                left to wait."
+            )),
+            (Verdict::RateLimited, None)
+        );
+        assert_eq!(
+            parse_response(&page(
+                "That's not the right answer.  Please wait \
+                999999999999999999 minutes before trying again."
+            )),
+            (Verdict::Wrong, None)
+        );
+    }
+
+    #[test]
+    fn submits_and_records() {
+        let tmp = TempDir::new().unwrap();
+        let path = history_path(tmp.path());
+        let http = FakeHttp::new(200, &page(TOO_HIGH));
+
+        assert_eq!(
+            submit_answer(&http, &path, 2023, 7, 2, "1000", 100, session),
+            Ok(Verdict::TooHigh)
+        );
+        assert_eq!(
+            *http.requests.borrow(),
+            [Request {
+                method: "POST",
+                path: "/2023/day/7/answer".to_string(),
+                session: "abc".to_string(),
+                form: vec![
+                    ("level".to_string(), "2".to_string()),
+                    ("answer".to_string(), "1000".to_string())
+                ],
+            }]
+        );
+
+        let history = History::load(&path).unwrap();
+        assert_eq!(history.wait_until, Some(160));
+        assert_eq!(
+            history.submissions,
+            [Submission {
+                day: 7,
+                part: 2,
+                answer: "1000".to_string(),
+                verdict: Verdict::TooHigh,
+                time: 100,
+            }]
+        );
+
+        // Neither the same answer nor a higher one is sent again
+        let no_session = || Err("session must not be needed".to_string());
+        for answer in ["1000", "2000"] {
+            let e = submit_answer(&http, &path, 2023, 7, 2, answer, 1000, no_session).unwrap_err();
+            assert!(e.contains("too high"), "{}", e);
+        }
+        assert_eq!(http.requests.borrow().len(), 1);
+
+        // A wait beyond the end of time keeps blocking instead of overflowing
+        let http = FakeHttp::new(200, &page(HUGE_WAIT));
+        assert_eq!(
+            submit_answer(&http, &path, 2023, 7, 2, "500", 1000, session),
+            Ok(Verdict::RateLimited)
+        );
+        assert_eq!(History::load(&path).unwrap().wait_until, Some(u64::MAX));
+        let e = submit_answer(&http, &path, 2023, 7, 2, "500", 2000, no_session).unwrap_err();
+        assert!(e.contains("wait"), "{}", e);
+    }
+
+    #[test]
+    fn rate_limit_is_not_a_wrong_answer() {
+        let tmp = TempDir::new().unwrap();
+        let path = history_path(tmp.path());
+        let http = FakeHttp::new(200, &page(TOO_RECENT));
+
+        assert_eq!(
+            submit_answer(&http, &path, 2023, 7, 1, "42", 100, session),
+            Ok(Verdict::RateLimited)
+        );
+        let e = submit_answer(&http, &path, 2023, 7, 1, "42", 150, session).unwrap_err();
+        assert!(e.contains("22s"), "{}", e);
+        assert_eq!(
+            submit_answer(&http, &path, 2023, 7, 1, "42", 172, session),
+            Ok(Verdict::RateLimited)
+        );
+    }
+
+    #[test]
+    fn check_bounds() {
+        let mut history = History::default();
+        history.record(3, 1, "100", &Verdict::TooHigh, None, 0);
+        history.record(3, 1, "80", &Verdict::TooHigh, None, 0);
+        history.record(3, 1, "10", &Verdict::TooLow, None, 0);
+        history.record(3, 1, "50", &Verdict::Wrong, None, 0);
+
+        assert!(history.check(3, 1, "80", 0).is_err());
+        assert!(history.check(3, 1, "90", 0).is_err());
+        assert!(history.check(3, 1, "10", 0).is_err());
+        assert!(history.check(3, 1, "-5", 0).is_err());
+        assert!(history.check(3, 1, "50", 0).is_err());
+        assert_eq!(history.check(3, 1, "79", 0), Ok(()));
+        assert_eq!(history.check(3, 1, "11", 0), Ok(()));
+        // Other parts and days are not affected
+        assert_eq!(history.check(3, 2, "90", 0), Ok(()));
+        assert_eq!(history.check(4, 1, "90", 0), Ok(()));
+
+        history.record(3, 1, "42", &Verdict::Correct, None, 0);
+        let e = history.check(3, 1, "43", 0).unwrap_err();
+        assert!(e.contains("solved already"), "{}", e);
+    }
+
+    #[test]
+    fn http_errors_are_not_recorded() {
+        let tmp = TempDir::new().unwrap();
+        let path = history_path(tmp.path());
+        let http = FakeHttp::new(500, "Internal Server Error");
+
+        assert!(submit_answer(&http, &path, 2023, 7, 1, "42", 100, session).is_err());
+        assert!(!path.exists());
+    }
+}
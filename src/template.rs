@@ -1,10 +1,36 @@
-use crate::util::file_lines;
-use itertools::Itertools;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+use crate::util;
 
-pub fn part1() {
-    let lines = file_lines("inp18_1.txt");
+fn parse(lines: &[String]) -> Result<Vec<String>> {
+    util::parse_lines(lines, |line| match line.is_empty() {
+        true => Err(Error::expected("a non-empty line")),
+        false => Ok(line.to_string()),
+    })
 }
 
-pub fn part2() {
-    let lines = file_lines("inp18_1.txt");
+fn part1(_input: &[String]) -> Answer {
+    0.into()
+}
+
+fn part2(_input: &[String]) -> Answer {
+    0.into()
+}
+
+pub struct DayN;
+
+impl Solution for DayN {
+    type Input = Vec<String>;
+
+    fn parse(lines: &[String]) -> Result<Self::Input> {
+        parse(lines)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input)
+    }
 }
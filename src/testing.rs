@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use proptest::prelude::*;
+
+use crate::input::{InputVariant, Inputs};
+use crate::solution::Solution;
+use crate::util::file_lines;
+
+/// Inputs and answers in the crate directory
+fn inputs() -> Inputs {
+    Inputs::flat(Path::new(env!("CARGO_MANIFEST_DIR")), 2023)
+}
+
+/// Lines of the input file `variant` of `day` from the crate directory
+pub fn input_lines(day: u8, variant: InputVariant) -> Vec<String> {
+    file_lines(inputs().input_path(day, variant)).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Parsed input file `variant` of `day` from the crate directory
+pub fn input<S: Solution>(day: u8, variant: InputVariant) -> S::Input {
+    let path = inputs().input_path(day, variant);
+    S::parse(&input_lines(day, variant)).unwrap_or_else(|e| panic!("{}", e.in_file(&path)))
+}
+
+/// Check both parts of the real input of `day` against `answers.toml`
+///
+/// Real inputs are personal, so nothing is checked if the input file or its answers are not
+/// there.
+pub fn check_real<S: Solution>(day: u8) {
+    if !inputs().input_path(day, InputVariant::Real).exists() {
+        eprintln!("Day {}: no real input, skipped", day);
+        return;
+    }
+    let answers = inputs().answers().unwrap();
+    let input = input::<S>(day, InputVariant::Real);
+    for part in 1..=2 {
+        let Some(expected) = answers.expected(day, InputVariant::Real, part) else {
+            eprintln!("Day {} part {}: no real answer, skipped", day, part);
+            continue;
+        };
+        let answer = match part {
+            1 => S::part1(&input),
+            _ => S::part2(&input),
+        };
+        assert!(
+            expected.matches(&answer),
+            "Day {} part {}: expected {}, got {}",
+            day,
+            part,
+            expected,
+            answer
+        );
+    }
+}
+
+/// Environment variable that makes [`check_real_slow`] run, e.g. `AOC_SLOW_TESTS=1`
+const SLOW_VAR: &str = "AOC_SLOW_TESTS";
+
+/// [`check_real`] for days that take minutes, only if [`SLOW_VAR`] is set
+pub fn check_real_slow<S: Solution>(day: u8) {
+    if std::env::var_os(SLOW_VAR).is_none() {
+        eprintln!(
+            "Day {}: slow, set {} to check the real input",
+            day, SLOW_VAR
+        );
+        return;
+    }
+    check_real::<S>(day);
+}
+
+/// Rectangular grids of up to 8x8 tiles picked from `tiles`, as the lines
+/// [`build_grid`](crate::util::build_grid) reads
+pub fn grid_text(tiles: &str) -> impl Strategy<Value = Vec<String>> {
+    let tiles: Vec<char> = tiles.chars().collect();
+    (1..=8_usize, 1..=8_usize).prop_flat_map(move |(rows, cols)| {
+        let row = prop::collection::vec(prop::sample::select(tiles.clone()), cols);
+        prop::collection::vec(row.prop_map(String::from_iter), rows)
+    })
+}
+
+/// Replace the tile in `row` that `col` picks among the tiles of the row
+pub fn set_tile(lines: &mut [String], row: usize, col: prop::sample::Index, tile: char) {
+    let mut chars: Vec<char> = lines[row].chars().collect();
+    let col = col.index(chars.len());
+    chars[col] = tile;
+    lines[row] = String::from_iter(chars);
+}
@@ -2,61 +2,150 @@ use std::{
     fs::File,
     io::{self, BufRead},
     path::Path,
+    time::{Duration, Instant},
 };
 
+use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
 use std::str::FromStr;
 
 use grid::Grid;
-use itertools::Itertools;
 use num::Integer;
 
-pub fn parse_ints<T>(s: &str) -> Vec<T>
-where
-    T: FromStr,
-    <T as FromStr>::Err: Debug,
-{
-    s.split(|c| c == ',')
-        .map(|p| p.trim().parse::<T>().unwrap())
-        .collect_vec()
+use crate::error::{Error, Result};
+
+/// Parse a comma separated list
+pub fn parse_ints<T: FromStr>(s: &str) -> Result<Vec<T>> {
+    parse_ints_in(s, s)
 }
 
-pub fn file_lines(filename: &str) -> Vec<String> {
-    let file = File::open(Path::new(filename)).unwrap();
+/// Parse a comma separated list `s` that is part of `line`, for error columns relative to `line`
+pub fn parse_ints_in<T: FromStr>(line: &str, s: &str) -> Result<Vec<T>> {
+    s.split(',')
+        .map(|p| {
+            p.trim()
+                .parse::<T>()
+                .map_err(|_| Error::expected_at(line, p.trim(), "a number"))
+        })
+        .collect()
+}
+
+pub fn file_lines<P: AsRef<Path>>(filename: P) -> Result<Vec<String>> {
+    let filename = filename.as_ref();
+    let io_error = |source| Error::Io {
+        path: filename.to_path_buf(),
+        source,
+    };
+    let file = File::open(filename).map_err(io_error)?;
     io::BufReader::new(file)
         .lines()
-        .map(|l| l.unwrap())
-        .collect_vec()
+        .collect::<io::Result<Vec<_>>>()
+        .map_err(io_error)
+}
+
+/// Parse every line with `parse_fn`, attaching the line number to errors
+pub fn parse_lines<T, F>(lines: &[String], parse_fn: F) -> Result<Vec<T>>
+where
+    F: Fn(&str) -> Result<T>,
+{
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, l)| parse_fn(l).map_err(|e| e.in_line(i)))
+        .collect()
+}
+
+pub fn parse_digit(c: char) -> Result<u8> {
+    c.to_digit(10)
+        .map(|d| d as u8)
+        .ok_or_else(|| Error::expected("a digit"))
+}
+
+/// Tile of a grid that is written as a single character in the input
+pub trait GridTile: Sized {
+    fn from_char(c: char) -> Result<Self>;
+
+    /// Character that [`GridTile::from_char`] parses back to the same tile
+    fn to_char(&self) -> char;
+}
+
+/// `#` for rock or wall, `.` for open ground
+impl GridTile for bool {
+    fn from_char(c: char) -> Result<bool> {
+        match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(Error::expected("'#' or '.'")),
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            true => '#',
+            false => '.',
+        }
+    }
 }
 
-pub fn parse_digit(c: char) -> u8 {
-    c.to_digit(10).unwrap() as u8
+/// Build a grid of typed tiles, attaching the position to errors
+pub fn build_grid<T: GridTile>(lines: &[String]) -> Result<Grid<T>> {
+    build_grid_with(lines, T::from_char)
 }
 
-pub fn build_grid<T, F>(lines: &[String], map_fn: F) -> Grid<T>
+/// Build a grid from one char per tile, attaching the position to errors
+pub fn build_grid_with<T, F>(lines: &[String], map_fn: F) -> Result<Grid<T>>
 where
-    F: Fn(char) -> T,
+    F: Fn(char) -> Result<T>,
 {
-    let first_line = &lines[0];
-    let grid_vec = lines
-        .iter()
-        .flat_map(|l| l.chars().map(&map_fn).collect_vec())
-        .collect_vec();
-    Grid::from_vec(grid_vec, first_line.len())
+    let first_line = lines.first().ok_or_else(|| Error::expected("a grid"))?;
+    let cols = first_line.chars().count();
+    if cols == 0 {
+        return Err(Error::expected("a grid").in_line(0));
+    }
+    let mut grid_vec = Vec::with_capacity(cols * lines.len());
+    for (row, line) in lines.iter().enumerate() {
+        let mut len = 0;
+        for (col, c) in line.chars().enumerate() {
+            if col == cols {
+                return Err(Error::expected(format!("{} tiles per row", cols))
+                    .in_line(row)
+                    .at_column(col));
+            }
+            grid_vec.push(map_fn(c).map_err(|e| e.in_line(row).at_column(col))?);
+            len += 1;
+        }
+        if len != cols {
+            return Err(Error::expected(format!("{} tiles per row", cols))
+                .in_line(row)
+                .at_column(len));
+        }
+    }
+    Ok(Grid::from_vec(grid_vec, cols))
 }
 
-pub fn print_grid<T, F>(grid: &Grid<T>, map_fn: F)
+/// Render a grid of typed tiles as the text [`build_grid`] reads
+pub fn render_grid<T: GridTile>(grid: &Grid<T>) -> String {
+    format_grid(grid, T::to_char)
+}
+
+/// Run `f` and measure its wall time
+pub fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let res = f();
+    (res, start.elapsed())
+}
+
+/// Render a grid row by row, e.g. for debug logging
+pub fn format_grid<T, F>(grid: &Grid<T>, map_fn: F) -> String
 where
     F: Fn(&T) -> char,
 {
-    println!(
-        "{}",
-        grid.iter_rows()
-            .map(|row| String::from_iter(row.map(&map_fn)))
-            .collect::<Vec<String>>()
-            .join("\n")
-    );
+    grid.iter_rows()
+        .map(|row| String::from_iter(row.map(&map_fn)))
+        .collect::<Vec<String>>()
+        .join("\n")
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Hash, enum_iterator::Sequence)]
@@ -97,12 +186,7 @@ impl Direction {
         }
     }
     pub fn repr(&self) -> char {
-        match self {
-            Direction::N => '↑',
-            Direction::E => '→',
-            Direction::S => '↓',
-            Direction::W => '←',
-        }
+        self.to_char(DirectionScheme::Arrow)
     }
     pub fn is_horizontal(self) -> bool {
         self == Direction::E || self == Direction::W
@@ -122,14 +206,258 @@ impl Debug for Direction {
     }
 }
 
+/// Compass letter, parsed back by [`FromStr`]
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.display(DirectionScheme::Compass), f)
+    }
+}
+
+/// Character of a [`Direction`] in one [`DirectionScheme`], see [`Direction::display`]
+#[derive(Debug, Clone, Copy)]
+pub struct DirectionDisplay {
+    dir: Direction,
+    scheme: DirectionScheme,
+}
+
+impl fmt::Display for DirectionDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.dir.to_char(self.scheme))
+    }
+}
+
+/// Any single character of a [`DirectionScheme`]
+impl TryFrom<char> for Direction {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Direction> {
+        DirectionScheme::all()
+            .find_map(|scheme| Direction::from_char(c, scheme).ok())
+            .ok_or_else(|| Error::expected("a direction (NESW, UDLR, ^>v<, ↑→↓← or 0 to 3)"))
+    }
+}
+
+impl FromStr for Direction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Direction> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => c.try_into(),
+            _ => Err(Error::expected("a single direction character")),
+        }
+    }
+}
+
+impl Direction {
+    /// Parse `c` as written in `scheme` only
+    pub fn from_char(c: char, scheme: DirectionScheme) -> Result<Direction> {
+        Direction::all()
+            .find(|&dir| dir.to_char(scheme) == c)
+            .ok_or_else(|| Error::expected(format!("a direction ({})", scheme.description())))
+    }
+
+    /// Show as written in `scheme`, parsed back by [`Direction::from_char`] and [`FromStr`]
+    pub fn display(self, scheme: DirectionScheme) -> DirectionDisplay {
+        DirectionDisplay { dir: self, scheme }
+    }
+
+    pub fn to_char(self, scheme: DirectionScheme) -> char {
+        let chars = match scheme {
+            DirectionScheme::Compass => ['N', 'E', 'S', 'W'],
+            DirectionScheme::UpDown => ['U', 'R', 'D', 'L'],
+            DirectionScheme::AsciiArrow => ['^', '>', 'v', '<'],
+            DirectionScheme::Arrow => ['↑', '→', '↓', '←'],
+            DirectionScheme::Digit => ['3', '0', '1', '2'],
+        };
+        chars[self as usize]
+    }
+}
+
+/// Ways the puzzles write a [`Direction`] as a single character
+#[derive(Debug, PartialEq, Eq, Clone, Copy, enum_iterator::Sequence)]
+pub enum DirectionScheme {
+    /// `N`, `E`, `S`, `W`
+    Compass,
+    /// `U`, `R`, `D`, `L`
+    UpDown,
+    /// `^`, `>`, `v`, `<`
+    AsciiArrow,
+    /// `↑`, `→`, `↓`, `←`, as shown by [`Direction::repr`]
+    Arrow,
+    /// `0` to `3` clockwise starting east
+    Digit,
+}
+
+impl DirectionScheme {
+    pub fn all() -> enum_iterator::All<DirectionScheme> {
+        enum_iterator::all::<Self>()
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            DirectionScheme::Compass => "N, E, S or W",
+            DirectionScheme::UpDown => "U, R, D or L",
+            DirectionScheme::AsciiArrow => "^, >, v or <",
+            DirectionScheme::Arrow => "↑, →, ↓ or ←",
+            DirectionScheme::Digit => "0 to 3",
+        }
+    }
+}
+
+/// Direction including the diagonals, in clockwise order starting north
+#[derive(PartialEq, Eq, Clone, Copy, Hash, enum_iterator::Sequence)]
+pub enum Direction8 {
+    N = 0,
+    NE = 1,
+    E = 2,
+    SE = 3,
+    S = 4,
+    SW = 5,
+    W = 6,
+    NW = 7,
+}
+
+impl Direction8 {
+    const CLOCKWISE: [Direction8; 8] = [
+        Direction8::N,
+        Direction8::NE,
+        Direction8::E,
+        Direction8::SE,
+        Direction8::S,
+        Direction8::SW,
+        Direction8::W,
+        Direction8::NW,
+    ];
+
+    pub fn all() -> enum_iterator::All<Direction8> {
+        enum_iterator::all::<Self>()
+    }
+
+    /// Rotate clockwise by `eighths` of a full turn
+    fn rot_by(self, eighths: usize) -> Direction8 {
+        Self::CLOCKWISE[(self as usize + eighths) % 8]
+    }
+
+    pub fn opposite(self) -> Direction8 {
+        self.rot_by(4)
+    }
+    /// Rotate by 45° clockwise
+    pub fn rot_cw(self) -> Direction8 {
+        self.rot_by(1)
+    }
+    /// Rotate by 45° counterclockwise
+    pub fn rot_ccw(self) -> Direction8 {
+        self.rot_by(7)
+    }
+    pub fn rot_cw_90(self) -> Direction8 {
+        self.rot_by(2)
+    }
+    pub fn rot_ccw_90(self) -> Direction8 {
+        self.rot_by(6)
+    }
+    pub fn repr(&self) -> char {
+        match self {
+            Direction8::N => '↑',
+            Direction8::NE => '↗',
+            Direction8::E => '→',
+            Direction8::SE => '↘',
+            Direction8::S => '↓',
+            Direction8::SW => '↙',
+            Direction8::W => '←',
+            Direction8::NW => '↖',
+        }
+    }
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+}
+
+impl Debug for Direction8 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.repr())
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(dir: Direction) -> Direction8 {
+        match dir {
+            Direction::N => Direction8::N,
+            Direction::E => Direction8::E,
+            Direction::S => Direction8::S,
+            Direction::W => Direction8::W,
+        }
+    }
+}
+
+/// Fails for the diagonals
+impl TryFrom<Direction8> for Direction {
+    type Error = Direction8;
+
+    fn try_from(dir: Direction8) -> std::result::Result<Direction, Direction8> {
+        match dir {
+            Direction8::N => Ok(Direction::N),
+            Direction8::E => Ok(Direction::E),
+            Direction8::S => Ok(Direction::S),
+            Direction8::W => Ok(Direction::W),
+            _ => Err(dir),
+        }
+    }
+}
+
+/// Offsets of the tiles around a [`Position`] that count as its neighbours
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Neighbourhood<'a> {
+    /// The four orthogonally adjacent tiles
+    VonNeumann,
+    /// The eight tiles around, including the diagonals
+    Moore,
+    /// Any other stencil
+    Custom(&'a [Vector]),
+}
+
+impl<'a> Neighbourhood<'a> {
+    const VON_NEUMANN: [Vector; 4] = [
+        Vector { x: 0, y: -1 },
+        Vector { x: 1, y: 0 },
+        Vector { x: 0, y: 1 },
+        Vector { x: -1, y: 0 },
+    ];
+    const MOORE: [Vector; 8] = [
+        Vector { x: 0, y: -1 },
+        Vector { x: 1, y: -1 },
+        Vector { x: 1, y: 0 },
+        Vector { x: 1, y: 1 },
+        Vector { x: 0, y: 1 },
+        Vector { x: -1, y: 1 },
+        Vector { x: -1, y: 0 },
+        Vector { x: -1, y: -1 },
+    ];
+
+    /// Offsets in clockwise order starting north for the built-in neighbourhoods
+    pub fn offsets(self) -> &'a [Vector] {
+        match self {
+            Neighbourhood::VonNeumann => &Self::VON_NEUMANN,
+            Neighbourhood::Moore => &Self::MOORE,
+            Neighbourhood::Custom(offsets) => offsets,
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
 pub struct Position {
     pub x: isize,
     pub y: isize,
 }
 impl Position {
+    pub const ORIGIN: Position = Position { x: 0, y: 0 };
+
+    /// (row, col) to index a grid with, panics for negative coordinates
+    ///
+    /// Use `try_into()` for positions that can be outside of the grid.
     pub fn as_grid_pos(self) -> (usize, usize) {
-        (self.y as usize, self.x as usize)
+        self.try_into()
+            .unwrap_or_else(|p| panic!("{:?} has no grid position", p))
     }
 
     pub fn as_grid_pos_in_repeating_grid<T>(self, grid: &Grid<T>) -> (usize, usize) {
@@ -140,24 +468,7 @@ impl Position {
     }
 
     pub fn advance_in_dir_by(self, dir: Direction, extent: isize) -> Position {
-        match dir {
-            Direction::N => Position {
-                x: self.x,
-                y: self.y - extent,
-            },
-            Direction::E => Position {
-                x: self.x + extent,
-                y: self.y,
-            },
-            Direction::S => Position {
-                x: self.x,
-                y: self.y + extent,
-            },
-            Direction::W => Position {
-                x: self.x - extent,
-                y: self.y,
-            },
-        }
+        self + Vector::from(dir) * extent
     }
 
     pub fn advance_in_grid<T>(self, dir: Direction, grid: &Grid<T>) -> Option<Position> {
@@ -182,8 +493,57 @@ impl Position {
         }
     }
 
+    pub fn is_in_grid<T>(self, grid: &Grid<T>) -> bool {
+        (0..grid.cols() as isize).contains(&self.x) && (0..grid.rows() as isize).contains(&self.y)
+    }
+
+    pub fn neighbours(
+        self,
+        neighbourhood: Neighbourhood<'_>,
+    ) -> impl Iterator<Item = Position> + '_ {
+        neighbourhood
+            .offsets()
+            .iter()
+            .map(move |&offset| self + offset)
+    }
+
+    /// Neighbours that lie inside of `grid`
+    pub fn neighbours_in_grid<'a, T>(
+        self,
+        neighbourhood: Neighbourhood<'a>,
+        grid: &'a Grid<T>,
+    ) -> impl Iterator<Item = Position> + 'a {
+        self.neighbours(neighbourhood)
+            .filter(|pos| pos.is_in_grid(grid))
+    }
+
     pub fn manhattan_distance_to(self, other: Position) -> usize {
-        (other.x - self.x).unsigned_abs() + (other.y - self.y).unsigned_abs()
+        (other - self).manhattan_length()
+    }
+
+    /// Distance when diagonal steps are allowed, like a king moves
+    pub fn chebyshev_distance_to(self, other: Position) -> usize {
+        (other - self).chebyshev_length()
+    }
+
+    pub fn euclidean_distance_to(self, other: Position) -> f64 {
+        (other - self).euclidean_length()
+    }
+
+    /// Rotate by 90° clockwise about the origin, as shown with y growing downwards
+    pub fn rot_cw(self) -> Position {
+        Position {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
+    /// Rotate by 90° counterclockwise about the origin, as shown with y growing downwards
+    pub fn rot_ccw(self) -> Position {
+        Position {
+            x: self.y,
+            y: -self.x,
+        }
     }
 
     pub fn from_grid_pos(p: (usize, usize)) -> Position {
@@ -199,3 +559,695 @@ impl Debug for Position {
         write!(f, "({},{})", self.x, self.y)
     }
 }
+
+/// Grid coordinates as used by [`Grid`], (row, column)
+impl From<(usize, usize)> for Position {
+    fn from(p: (usize, usize)) -> Position {
+        Position::from_grid_pos(p)
+    }
+}
+
+/// Grid coordinates as used by [`Grid`], (row, column), fails for negative coordinates
+impl TryFrom<Position> for (usize, usize) {
+    type Error = Position;
+
+    fn try_from(p: Position) -> std::result::Result<(usize, usize), Position> {
+        match (usize::try_from(p.y), usize::try_from(p.x)) {
+            (Ok(row), Ok(col)) => Ok((row, col)),
+            _ => Err(p),
+        }
+    }
+}
+
+/// (x, y)
+impl From<(isize, isize)> for Position {
+    fn from((x, y): (isize, isize)) -> Position {
+        Position { x, y }
+    }
+}
+
+/// (x, y)
+impl From<Position> for (isize, isize) {
+    fn from(p: Position) -> (isize, isize) {
+        (p.x, p.y)
+    }
+}
+
+impl Add<Vector> for Position {
+    type Output = Position;
+
+    fn add(self, v: Vector) -> Position {
+        Position {
+            x: self.x + v.x,
+            y: self.y + v.y,
+        }
+    }
+}
+
+impl AddAssign<Vector> for Position {
+    fn add_assign(&mut self, v: Vector) {
+        *self = *self + v;
+    }
+}
+
+impl Sub<Vector> for Position {
+    type Output = Position;
+
+    fn sub(self, v: Vector) -> Position {
+        self + -v
+    }
+}
+
+impl SubAssign<Vector> for Position {
+    fn sub_assign(&mut self, v: Vector) {
+        *self = *self - v;
+    }
+}
+
+/// Offset from `other` to `self`
+impl Sub for Position {
+    type Output = Vector;
+
+    fn sub(self, other: Position) -> Vector {
+        Vector {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+/// Offset between two [`Position`]s
+#[derive(PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord, Default)]
+pub struct Vector {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Vector {
+    pub fn manhattan_length(self) -> usize {
+        self.x.unsigned_abs() + self.y.unsigned_abs()
+    }
+
+    pub fn chebyshev_length(self) -> usize {
+        self.x.unsigned_abs().max(self.y.unsigned_abs())
+    }
+
+    pub fn euclidean_length(self) -> f64 {
+        (self.x as f64).hypot(self.y as f64)
+    }
+
+    /// Rotate by 90° clockwise, as shown with y growing downwards
+    pub fn rot_cw(self) -> Vector {
+        Vector {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
+    /// Rotate by 90° counterclockwise, as shown with y growing downwards
+    pub fn rot_ccw(self) -> Vector {
+        Vector {
+            x: self.y,
+            y: -self.x,
+        }
+    }
+}
+
+impl Debug for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{},{}>", self.x, self.y)
+    }
+}
+
+/// Step of length 1, north is towards smaller y
+impl From<Direction> for Vector {
+    fn from(dir: Direction) -> Vector {
+        match dir {
+            Direction::N => Vector { x: 0, y: -1 },
+            Direction::E => Vector { x: 1, y: 0 },
+            Direction::S => Vector { x: 0, y: 1 },
+            Direction::W => Vector { x: -1, y: 0 },
+        }
+    }
+}
+
+/// Step of length 1 along each axis it moves on, north is towards smaller y
+impl From<Direction8> for Vector {
+    fn from(dir: Direction8) -> Vector {
+        Neighbourhood::MOORE[dir as usize]
+    }
+}
+
+/// (x, y)
+impl From<(isize, isize)> for Vector {
+    fn from((x, y): (isize, isize)) -> Vector {
+        Vector { x, y }
+    }
+}
+
+/// (x, y)
+impl From<Vector> for (isize, isize) {
+    fn from(v: Vector) -> (isize, isize) {
+        (v.x, v.y)
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Vector {
+        Vector {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl AddAssign for Vector {
+    fn add_assign(&mut self, other: Vector) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, other: Vector) -> Vector {
+        self + -other
+    }
+}
+
+impl SubAssign for Vector {
+    fn sub_assign(&mut self, other: Vector) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl Mul<isize> for Vector {
+    type Output = Vector;
+
+    fn mul(self, factor: isize) -> Vector {
+        Vector {
+            x: self.x * factor,
+            y: self.y * factor,
+        }
+    }
+}
+
+/// Grid without a fixed size, only the tiles that were set are stored
+///
+/// The bounding box grows to cover every tile inserted, it does not shrink when tiles are
+/// removed.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    tiles: HashMap<Position, T>,
+    bounds: Option<(Position, Position)>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            tiles: HashMap::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Smallest and largest corner of all tiles ever inserted, both inclusive
+    pub fn bounds(&self) -> Option<(Position, Position)> {
+        self.bounds
+    }
+
+    pub fn in_bounds(&self, pos: Position) -> bool {
+        self.bounds.is_some_and(|(min, max)| {
+            (min.x..=max.x).contains(&pos.x) && (min.y..=max.y).contains(&pos.y)
+        })
+    }
+
+    pub fn get(&self, pos: Position) -> Option<&T> {
+        self.tiles.get(&pos)
+    }
+
+    pub fn get_mut(&mut self, pos: Position) -> Option<&mut T> {
+        self.tiles.get_mut(&pos)
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        self.tiles.contains_key(&pos)
+    }
+
+    /// Set the tile at `pos`, returning the previous one
+    pub fn insert(&mut self, pos: Position, tile: T) -> Option<T> {
+        self.grow_to(pos);
+        self.tiles.insert(pos, tile)
+    }
+
+    pub fn get_or_insert_with(&mut self, pos: Position, f: impl FnOnce() -> T) -> &mut T {
+        self.grow_to(pos);
+        self.tiles.entry(pos).or_insert_with(f)
+    }
+
+    pub fn remove(&mut self, pos: Position) -> Option<T> {
+        self.tiles.remove(&pos)
+    }
+
+    fn grow_to(&mut self, pos: Position) {
+        self.bounds = Some(match self.bounds {
+            None => (pos, pos),
+            Some((min, max)) => (
+                Position {
+                    x: min.x.min(pos.x),
+                    y: min.y.min(pos.y),
+                },
+                Position {
+                    x: max.x.max(pos.x),
+                    y: max.y.max(pos.y),
+                },
+            ),
+        });
+    }
+
+    /// Tiles in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.tiles.iter().map(|(&pos, tile)| (pos, tile))
+    }
+
+    /// Neighbours of `pos` that are set
+    pub fn neighbours<'a>(
+        &'a self,
+        pos: Position,
+        neighbourhood: Neighbourhood<'a>,
+    ) -> impl Iterator<Item = (Position, &'a T)> + 'a {
+        pos.neighbours(neighbourhood)
+            .filter_map(|pos| self.get(pos).map(|tile| (pos, tile)))
+    }
+
+    /// Render the bounding box row by row, `map_fn` gets `None` for tiles that are not set
+    pub fn format<F>(&self, map_fn: F) -> String
+    where
+        F: Fn(Option<&T>) -> char,
+    {
+        let Some((min, max)) = self.bounds else {
+            return String::new();
+        };
+        (min.y..=max.y)
+            .map(|y| {
+                (min.x..=max.x)
+                    .map(|x| map_fn(self.get(Position { x, y })))
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+impl<T> FromIterator<(Position, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Position, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        for (pos, tile) in iter {
+            grid.insert(pos, tile);
+        }
+        grid
+    }
+}
+
+/// Panics if the tile is not set
+impl<T> Index<Position> for SparseGrid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Position) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("no tile at {:?}", pos))
+    }
+}
+
+/// Panics if the tile is not set
+impl<T> IndexMut<Position> for SparseGrid<T> {
+    fn index_mut(&mut self, pos: Position) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("no tile at {:?}", pos))
+    }
+}
+
+/// Finite grid repeated infinitely in every direction, the original is the copy at the origin
+#[derive(Debug)]
+pub struct TiledGrid<'a, T> {
+    grid: &'a Grid<T>,
+}
+
+// Derived impls would require `T: Clone`
+impl<T> Clone for TiledGrid<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TiledGrid<'_, T> {}
+
+impl<'a, T> TiledGrid<'a, T> {
+    pub fn new(grid: &'a Grid<T>) -> TiledGrid<'a, T> {
+        TiledGrid { grid }
+    }
+
+    pub fn get(&self, pos: Position) -> &'a T {
+        &self.grid[pos.as_grid_pos_in_repeating_grid(self.grid)]
+    }
+
+    /// Which copy of the grid `pos` lies in, counted in grid sizes from the origin
+    pub fn copy_of(&self, pos: Position) -> Vector {
+        Vector {
+            x: Integer::div_floor(&pos.x, &(self.grid.cols() as isize)),
+            y: Integer::div_floor(&pos.y, &(self.grid.rows() as isize)),
+        }
+    }
+
+    pub fn neighbours(
+        self,
+        pos: Position,
+        neighbourhood: Neighbourhood<'a>,
+    ) -> impl Iterator<Item = (Position, &'a T)> + 'a {
+        pos.neighbours(neighbourhood)
+            .map(move |pos| (pos, self.get(pos)))
+    }
+}
+
+impl<T> Index<Position> for TiledGrid<'_, T> {
+    type Output = T;
+
+    fn index(&self, pos: Position) -> &T {
+        self.get(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(x: isize, y: isize) -> Position {
+        Position { x, y }
+    }
+
+    fn vector(x: isize, y: isize) -> Vector {
+        Vector { x, y }
+    }
+
+    #[test]
+    fn position_arithmetic() {
+        let mut p = pos(2, 3);
+        assert_eq!(p + vector(1, -5), pos(3, -2));
+        assert_eq!(p - vector(1, -5), pos(1, 8));
+        assert_eq!(pos(3, -2) - p, vector(1, -5));
+        assert_eq!(p + (pos(7, 7) - p), pos(7, 7));
+        p += vector(-2, -3);
+        assert_eq!(p, Position::ORIGIN);
+        p -= vector(1, 1);
+        assert_eq!(p, pos(-1, -1));
+    }
+
+    #[test]
+    fn vector_arithmetic() {
+        let mut v = vector(2, -3);
+        assert_eq!(v + vector(1, 1), vector(3, -2));
+        assert_eq!(v - vector(1, 1), vector(1, -4));
+        assert_eq!(-v, vector(-2, 3));
+        assert_eq!(v * 3, vector(6, -9));
+        v += vector(1, 1);
+        assert_eq!(v, vector(3, -2));
+        v -= vector(3, -2);
+        assert_eq!(v, Vector::default());
+    }
+
+    #[test]
+    fn steps_in_directions() {
+        let p = pos(5, 5);
+        assert_eq!(p + Vector::from(Direction::N), pos(5, 4));
+        assert_eq!(p + Vector::from(Direction::E), pos(6, 5));
+        assert_eq!(p + Vector::from(Direction::S), pos(5, 6));
+        assert_eq!(p + Vector::from(Direction::W), pos(4, 5));
+        for dir in Direction::all() {
+            assert_eq!(p.advance_in_dir_by(dir, 3), p + Vector::from(dir) * 3);
+            assert_eq!(-Vector::from(dir), Vector::from(dir.opposite()));
+        }
+    }
+
+    #[test]
+    fn rotations() {
+        for dir in Direction::all() {
+            assert_eq!(Vector::from(dir).rot_cw(), Vector::from(dir.rot_cw()));
+            assert_eq!(Vector::from(dir).rot_ccw(), Vector::from(dir.rot_ccw()));
+        }
+        let p = pos(3, -1);
+        assert_eq!(p.rot_cw(), pos(1, 3));
+        assert_eq!(p.rot_ccw(), pos(-1, -3));
+        assert_eq!(p.rot_cw().rot_cw().rot_cw().rot_cw(), p);
+        assert_eq!(p.rot_cw().rot_ccw(), p);
+        assert_eq!(
+            p.rot_cw().rot_cw(),
+            Position::ORIGIN - (p - Position::ORIGIN)
+        );
+    }
+
+    #[test]
+    fn diagonal_directions() {
+        assert_eq!(Direction8::all().count(), 8);
+        for dir in Direction8::all() {
+            let v = Vector::from(dir);
+            assert_eq!(v.chebyshev_length(), 1);
+            assert_eq!(dir.is_diagonal(), v.manhattan_length() == 2);
+            assert_eq!(-v, Vector::from(dir.opposite()));
+            assert_eq!(v.rot_cw(), Vector::from(dir.rot_cw_90()));
+            assert_eq!(v.rot_ccw(), Vector::from(dir.rot_ccw_90()));
+            assert_eq!(dir.rot_cw().rot_cw(), dir.rot_cw_90());
+            assert_eq!(dir.rot_cw().rot_ccw(), dir);
+        }
+        assert_eq!(Direction8::N.rot_cw(), Direction8::NE);
+        assert_eq!(Direction8::N.rot_ccw(), Direction8::NW);
+        assert_eq!(Vector::from(Direction8::SW), vector(-1, 1));
+        for dir in Direction::all() {
+            assert_eq!(Direction::try_from(Direction8::from(dir)), Ok(dir));
+            assert_eq!(Vector::from(Direction8::from(dir)), Vector::from(dir));
+        }
+        assert_eq!(Direction::try_from(Direction8::SE), Err(Direction8::SE));
+    }
+
+    #[test]
+    fn direction_schemes() {
+        for scheme in DirectionScheme::all() {
+            for dir in Direction::all() {
+                let c = dir.to_char(scheme);
+                assert_eq!(Direction::from_char(c, scheme).unwrap(), dir);
+                assert_eq!(Direction::try_from(c).unwrap(), dir);
+            }
+        }
+        assert_eq!(
+            Direction::from_char('U', DirectionScheme::UpDown).unwrap(),
+            Direction::N
+        );
+        assert_eq!(
+            Direction::from_char('0', DirectionScheme::Digit).unwrap(),
+            Direction::E
+        );
+        assert_eq!(Direction::try_from('v').unwrap(), Direction::S);
+        assert_eq!(Direction::try_from('←').unwrap(), Direction::W);
+        assert_eq!(
+            Direction::from_char('N', DirectionScheme::UpDown)
+                .unwrap_err()
+                .to_string(),
+            "<input>: expected a direction (U, R, D or L)"
+        );
+        assert!(Direction::try_from('x').is_err());
+        assert!(Direction::try_from('4').is_err());
+    }
+
+    #[test]
+    fn direction_display() {
+        for dir in Direction::all() {
+            assert_eq!(dir.to_string().parse::<Direction>().unwrap(), dir);
+        }
+        for scheme in DirectionScheme::all() {
+            for dir in Direction::all() {
+                let s = format!("{}", dir.display(scheme));
+                let mut chars = s.chars();
+                let c = chars.next().unwrap();
+                assert_eq!(chars.next(), None);
+                assert_eq!(Direction::from_char(c, scheme).unwrap(), dir);
+                assert_eq!(s.parse::<Direction>().unwrap(), dir);
+            }
+        }
+        assert_eq!(Direction::W.to_string(), "W");
+        assert_eq!(
+            Direction::W.display(DirectionScheme::UpDown).to_string(),
+            "L"
+        );
+        assert_eq!(
+            Direction::N.display(DirectionScheme::Digit).to_string(),
+            "3"
+        );
+        assert_eq!("→".parse::<Direction>().unwrap(), Direction::E);
+        assert!("".parse::<Direction>().is_err());
+        assert!("NE".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn neighbourhoods() {
+        let p = pos(1, 1);
+        let von_neumann: Vec<_> = p.neighbours(Neighbourhood::VonNeumann).collect();
+        assert_eq!(von_neumann, [pos(1, 0), pos(2, 1), pos(1, 2), pos(0, 1)]);
+        let moore: Vec<_> = p.neighbours(Neighbourhood::Moore).collect();
+        assert_eq!(moore.len(), 8);
+        assert!(moore.iter().all(|&n| p.chebyshev_distance_to(n) == 1));
+        let knight = [vector(1, 2), vector(-2, 1)];
+        let custom: Vec<_> = p.neighbours(Neighbourhood::Custom(&knight)).collect();
+        assert_eq!(custom, [pos(2, 3), pos(-1, 2)]);
+
+        let grid: Grid<u8> = Grid::new(2, 3);
+        let corner: Vec<_> = Position::ORIGIN
+            .neighbours_in_grid(Neighbourhood::Moore, &grid)
+            .collect();
+        assert_eq!(corner, [pos(1, 0), pos(1, 1), pos(0, 1)]);
+        assert_eq!(
+            pos(1, 1)
+                .neighbours_in_grid(Neighbourhood::Moore, &grid)
+                .count(),
+            5
+        );
+        assert!(pos(2, 1).is_in_grid(&grid));
+        assert!(!pos(1, 2).is_in_grid(&grid));
+        assert!(!pos(-1, 0).is_in_grid(&grid));
+    }
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn typed_grid_round_trip() {
+        let text = lines("#..\n.#.\n..#");
+        let grid: Grid<bool> = build_grid(&text).unwrap();
+        assert!(grid[(1, 1)]);
+        assert!(!grid[(1, 2)]);
+        assert_eq!(render_grid(&grid), text.join("\n"));
+    }
+
+    #[test]
+    fn grid_errors() {
+        let error = |text| build_grid::<bool>(&lines(text)).unwrap_err().to_string();
+        assert_eq!(error("#.\n.x"), "<input>:2:2: expected '#' or '.'");
+        assert_eq!(
+            error("#.\n.#.\n##"),
+            "<input>:2:3: expected 2 tiles per row"
+        );
+        assert_eq!(error("#.\n.\n##"), "<input>:2:2: expected 2 tiles per row");
+        assert_eq!(error(""), "<input>: expected a grid");
+        assert!(build_grid::<bool>(&[String::new()]).is_err());
+    }
+
+    #[test]
+    fn sparse_grid() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.format(|_| '#'), "");
+        assert_eq!(grid.insert(pos(-2, 1), 'a'), None);
+        assert_eq!(grid.insert(pos(1, -1), 'b'), None);
+        assert_eq!(grid.insert(pos(1, -1), 'c'), Some('b'));
+        *grid.get_or_insert_with(pos(0, 0), || 'x') = 'd';
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.bounds(), Some((pos(-2, -1), pos(1, 1))));
+        assert!(grid.in_bounds(pos(-1, 0)));
+        assert!(!grid.in_bounds(pos(2, 0)));
+        assert_eq!(grid[pos(0, 0)], 'd');
+        assert_eq!(grid.get(pos(5, 5)), None);
+        assert_eq!(
+            grid.format(|tile| tile.copied().unwrap_or('.')),
+            "...c\n..d.\na..."
+        );
+
+        let mut neighbours: Vec<_> = grid.neighbours(pos(-1, 0), Neighbourhood::Moore).collect();
+        neighbours.sort();
+        assert_eq!(neighbours, [(pos(-2, 1), &'a'), (pos(0, 0), &'d')]);
+        assert_eq!(
+            grid.neighbours(pos(-1, 0), Neighbourhood::VonNeumann)
+                .count(),
+            1
+        );
+
+        assert_eq!(grid.remove(pos(-2, 1)), Some('a'));
+        assert!(!grid.contains(pos(-2, 1)));
+        assert_eq!(grid.bounds(), Some((pos(-2, -1), pos(1, 1))));
+        let copy: SparseGrid<char> = grid.iter().map(|(p, &t)| (p, t)).collect();
+        assert_eq!(copy.len(), 2);
+        assert_eq!(copy.bounds(), Some((pos(0, -1), pos(1, 0))));
+    }
+
+    #[test]
+    fn tiled_grid() {
+        let grid = Grid::from_vec(vec![1, 2, 3, 4, 5, 6], 3);
+        let tiled = TiledGrid::new(&grid);
+        assert_eq!(tiled[pos(0, 0)], 1);
+        assert_eq!(tiled[pos(2, 1)], 6);
+        assert_eq!(tiled[pos(-1, -1)], 6);
+        assert_eq!(tiled[pos(3, 2)], 1);
+        assert_eq!(tiled[pos(-7, 5)], 6);
+        assert_eq!(tiled.copy_of(pos(2, 1)), vector(0, 0));
+        assert_eq!(tiled.copy_of(pos(-1, 2)), vector(-1, 1));
+        let neighbours: Vec<_> = tiled
+            .neighbours(Position::ORIGIN, Neighbourhood::VonNeumann)
+            .map(|(_, &t)| t)
+            .collect();
+        assert_eq!(neighbours, [4, 2, 4, 3]);
+    }
+
+    #[test]
+    fn distances() {
+        let (a, b) = (pos(1, 1), pos(4, -3));
+        assert_eq!(a.manhattan_distance_to(b), 7);
+        assert_eq!(a.chebyshev_distance_to(b), 4);
+        assert_eq!(a.euclidean_distance_to(b), 5.0);
+        assert_eq!(b.euclidean_distance_to(a), 5.0);
+        assert_eq!(a.chebyshev_distance_to(a), 0);
+    }
+
+    #[test]
+    fn conversions() {
+        let p = pos(3, 7);
+        assert_eq!(Position::from((7_usize, 3_usize)), p);
+        assert_eq!(<(usize, usize)>::try_from(p), Ok((7, 3)));
+        assert_eq!(<(usize, usize)>::try_from(pos(-1, 7)), Err(pos(-1, 7)));
+        assert_eq!(<(usize, usize)>::try_from(pos(3, -7)), Err(pos(3, -7)));
+        assert_eq!(Position::from((3_isize, 7_isize)), p);
+        assert_eq!(<(isize, isize)>::from(p), (3, 7));
+        assert_eq!(Vector::from((-1_isize, 2_isize)), vector(-1, 2));
+        assert_eq!(<(isize, isize)>::from(vector(-1, 2)), (-1, 2));
+        assert_eq!(p.as_grid_pos(), (7, 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "has no grid position")]
+    fn negative_grid_pos() {
+        pos(-1, 7).as_grid_pos();
+    }
+}
@@ -0,0 +1,154 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+};
+
+use crate::answers::{Answers, ExpectedAnswer};
+use crate::input::{self, InputSource, InputVariant, Inputs};
+use crate::runner::panic_message;
+use crate::solution::{self, Answer, DynSolution, Solutions};
+
+#[derive(Clone)]
+enum Outcome {
+    Pass,
+    Fail(ExpectedAnswer, Answer),
+    Missing(Answer),
+    Panic(String),
+    Error(String),
+}
+
+#[derive(Default)]
+pub struct Summary {
+    passed: usize,
+    failed: usize,
+    missing: usize,
+    panicked: usize,
+    errors: usize,
+}
+
+impl Summary {
+    /// Whether a script should treat the run as successful
+    pub fn is_success(&self, strict: bool) -> bool {
+        self.failed == 0 && self.panicked == 0 && self.errors == 0 && (!strict || self.missing == 0)
+    }
+}
+
+fn check_part(
+    solution: &dyn DynSolution,
+    input: &Result<Box<dyn Any>, Outcome>,
+    expected: Option<&ExpectedAnswer>,
+    part: u8,
+) -> Outcome {
+    let input = match input {
+        Ok(input) => input,
+        Err(outcome) => return outcome.clone(),
+    };
+    let answer = panic::catch_unwind(AssertUnwindSafe(|| match part {
+        1 => solution.part1_any(&**input),
+        _ => solution.part2_any(&**input),
+    }));
+    match (answer, expected) {
+        (Err(payload), _) => Outcome::Panic(panic_message(&*payload)),
+        (Ok(answer), None) => Outcome::Missing(answer),
+        (Ok(answer), Some(expected)) if expected.matches(&answer) => Outcome::Pass,
+        (Ok(answer), Some(expected)) => Outcome::Fail(expected.clone(), answer),
+    }
+}
+
+/// [`verify`] against the answers file `answers_path` if given, or else against the answers
+/// files of the inputs
+pub fn verify_files(
+    solutions: &Solutions,
+    inputs: &Inputs,
+    answers_path: Option<&Path>,
+    day: Option<u8>,
+    variant: Option<InputVariant>,
+) -> Result<Summary, String> {
+    if let Some(day) = day {
+        solution::find(solutions, day)?;
+    }
+    let answers = match answers_path {
+        Some(path) => Answers::load(path)?,
+        None => inputs.answers()?,
+    };
+    Ok(verify(solutions, inputs, &answers, day, variant))
+}
+
+/// Run the solver of `day`, or of all days if not given, on all of their `inputs` and compare
+/// with `answers`
+pub fn verify(
+    solutions: &Solutions,
+    inputs: &Inputs,
+    answers: &Answers,
+    day: Option<u8>,
+    variant: Option<InputVariant>,
+) -> Summary {
+    let mut summary = Summary::default();
+
+    // Panics are reported per entry, don't clutter the output with the default message
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    for &(day, solution) in solutions
+        .iter()
+        .filter(|(d, _)| day.is_none_or(|day| *d == day))
+    {
+        let variants = inputs
+            .available_variants(day)
+            .into_iter()
+            .filter(|v| variant.is_none() || variant == Some(*v));
+        for v in variants {
+            let input = panic::catch_unwind(AssertUnwindSafe(|| {
+                input::load_input(solution, inputs, day, InputSource::File(v))
+            }));
+            let input = match input {
+                Ok(Ok(input)) => Ok(input),
+                Ok(Err(e)) => Err(Outcome::Error(e.to_string())),
+                Err(payload) => Err(Outcome::Panic(format!(
+                    "while parsing: {}",
+                    panic_message(&*payload)
+                ))),
+            };
+
+            for part in 1..=2 {
+                if !answers.applies(day, v, part) {
+                    continue;
+                }
+                let expected = answers.expected(day, v, part);
+                let outcome = check_part(solution, &input, expected, part);
+                let label = format!("Day {:2} part {} ({})", day, part, v);
+                match outcome {
+                    Outcome::Pass => {
+                        summary.passed += 1;
+                        println!("{:<24} pass", label);
+                    }
+                    Outcome::Fail(expected, actual) => {
+                        summary.failed += 1;
+                        println!("{:<24} FAIL: expected {}, got {}", label, expected, actual);
+                    }
+                    Outcome::Missing(actual) => {
+                        summary.missing += 1;
+                        println!("{:<24} missing answer (got {})", label, actual);
+                    }
+                    Outcome::Panic(msg) => {
+                        summary.panicked += 1;
+                        println!("{:<24} PANIC: {}", label, msg);
+                    }
+                    Outcome::Error(msg) => {
+                        summary.errors += 1;
+                        println!("{:<24} ERROR: {}", label, msg);
+                    }
+                }
+            }
+        }
+    }
+
+    panic::set_hook(default_hook);
+
+    println!(
+        "{} passed, {} failed, {} missing, {} panicked, {} errors",
+        summary.passed, summary.failed, summary.missing, summary.panicked, summary.errors
+    );
+    summary
+}
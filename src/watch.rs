@@ -0,0 +1,365 @@
+use std::{
+    any::Any,
+    collections::{BTreeMap, BTreeSet},
+    env,
+    ffi::OsString,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::answers::{Answers, ExpectedAnswer};
+use crate::input::{self, InputSource, InputVariant, Inputs};
+use crate::runner::{self, panic_message, PartOutcome};
+use crate::solution::{Answer, DynSolution};
+
+/// Environment variable that hands the results of the last run over to the rebuilt binary
+const PREVIOUS_VAR: &str = "AOC_WATCH_PREVIOUS";
+
+/// Time to wait for further events after a change, editors often write a file in several steps
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Answer of a part of a day on one input, or a description of why there is none
+type PartResult = Result<String, String>;
+
+type Results = BTreeMap<(InputVariant, u8), PartResult>;
+
+/// What a changed file means for the watched day
+#[derive(Debug, PartialEq, Eq)]
+enum Change {
+    Input(InputVariant),
+    Module,
+}
+
+fn classify(path: &Path, input_dirs: &[PathBuf], src_dir: &Path, day: u8) -> Option<Change> {
+    let name = path.file_name()?.to_str()?;
+    let dir = path.parent()?;
+    if dir == src_dir && name == format!("day{}.rs", day) {
+        Some(Change::Module)
+    } else if input_dirs.iter().any(|d| d == dir) {
+        InputVariant::from_file_name(day, name).map(Change::Input)
+    } else {
+        None
+    }
+}
+
+/// Solve the parts of `day` that apply to the input `variant`, like `verify` does
+fn run_input(
+    solution: &dyn DynSolution,
+    inputs: &Inputs,
+    answers: &Answers,
+    day: u8,
+    variant: InputVariant,
+) -> Vec<(u8, PartResult)> {
+    let input: Result<Box<dyn Any>, String> = panic::catch_unwind(AssertUnwindSafe(|| {
+        input::load_input(solution, inputs, day, InputSource::File(variant))
+    }))
+    .map_err(|payload| format!("panicked while parsing: {}", panic_message(&*payload)))
+    .and_then(|input| input.map_err(|e| e.to_string()));
+
+    (1..=2)
+        .filter(|&part| answers.applies(day, variant, part))
+        .map(|part| {
+            let result = match &input {
+                Ok(input) => {
+                    let part_run = runner::solve_part(solution, &**input, part);
+                    match &part_run.outcome {
+                        PartOutcome::Solved(answer) => Ok(answer.to_string()),
+                        _ => Err(part_run.error().unwrap()),
+                    }
+                }
+                Err(e) => Err(e.clone()),
+            };
+            (part, result)
+        })
+        .collect()
+}
+
+/// Result of a part compared with the previous run and the expected answer
+fn describe(
+    result: &PartResult,
+    previous: Option<&PartResult>,
+    expected: Option<&ExpectedAnswer>,
+) -> String {
+    let text = |r: &PartResult| match r {
+        Ok(answer) => answer.clone(),
+        Err(e) => format!("ERROR: {}", e),
+    };
+    let mut line = text(result);
+    match previous {
+        Some(previous) if previous != result => line += &format!(" (was {})", text(previous)),
+        Some(_) => line += " (unchanged)",
+        None => {}
+    }
+    match (result, expected) {
+        // The answer is only kept as text to survive restarts, compare it the way verify does
+        (Ok(answer), Some(expected)) if expected.matches(&Answer::from(answer.as_str())) => {
+            line += ", pass"
+        }
+        (_, Some(expected)) => line += &format!(", FAIL: expected {}", expected),
+        (_, None) => {}
+    }
+    line
+}
+
+/// Run the solver on `variants`, print the results compared with `previous` and remember them
+/// there
+fn run_and_report(
+    solution: &dyn DynSolution,
+    inputs: &Inputs,
+    answers_path: Option<&Path>,
+    day: u8,
+    variants: &BTreeSet<InputVariant>,
+    previous: &mut Results,
+) {
+    // The answers may have been edited since the last run
+    let answers = match answers_path {
+        Some(path) if !path.exists() => Ok(Answers::default()),
+        Some(path) => Answers::load(path),
+        None => inputs.answers(),
+    };
+    let answers = answers.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        Answers::default()
+    });
+    // Panics are reported per part, don't clutter the output with the default message
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    for &variant in variants {
+        for (part, result) in run_input(solution, inputs, &answers, day, variant) {
+            let line = describe(
+                &result,
+                previous.get(&(variant, part)),
+                answers.expected(day, variant, part),
+            );
+            let label = format!("Day {:2} part {} ({})", day, part, variant);
+            println!("{:<24} {}", label, line);
+            previous.insert((variant, part), result);
+        }
+    }
+    panic::set_hook(default_hook);
+}
+
+fn encode_results(results: &Results) -> String {
+    let list: Vec<_> = results
+        .iter()
+        .map(|((variant, part), result)| (variant.to_string(), part, result))
+        .collect();
+    serde_json::to_string(&list).unwrap()
+}
+
+fn decode_results(text: &str) -> Option<Results> {
+    let list: Vec<(String, u8, PartResult)> = serde_json::from_str(text).ok()?;
+    list.into_iter()
+        .map(|(variant, part, result)| Some(((variant.parse().ok()?, part), result)))
+        .collect()
+}
+
+/// Rebuild the crate and replace this process by the new binary, handing over `previous`
+///
+/// Only returns if that fails.
+fn rebuild_and_restart(crate_dir: &Path, exe: &Path, previous: &Results) -> String {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo"));
+    let mut build = Command::new(cargo);
+    build
+        .arg("build")
+        .arg("--manifest-path")
+        .arg(crate_dir.join("Cargo.toml"));
+    if !cfg!(debug_assertions) {
+        build.arg("--release");
+    }
+    match build.status() {
+        Ok(status) if status.success() => {}
+        Ok(_) => return "Build failed".to_string(),
+        Err(e) => return format!("Cannot run cargo: {}", e),
+    }
+
+    let mut restart = Command::new(exe);
+    restart
+        .args(env::args_os().skip(1))
+        .env(PREVIOUS_VAR, encode_results(previous));
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        format!("Cannot restart {}: {}", exe.display(), restart.exec())
+    }
+    #[cfg(not(unix))]
+    match restart.status() {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(e) => format!("Cannot restart {}: {}", exe.display(), e),
+    }
+}
+
+/// Run the solver of `day` on all of its inputs, then again whenever an input or the module of
+/// the day changes
+///
+/// A changed input is re-run on its own, a changed module is rebuilt and the new binary takes
+/// over. Every result is shown with its change from the previous run and compared with
+/// `answers_path`, or the answers next to the inputs. Only returns if watching fails.
+pub fn watch(
+    solution: &dyn DynSolution,
+    crate_dir: &Path,
+    inputs: &Inputs,
+    answers_path: Option<&Path>,
+    day: u8,
+) -> Result<(), String> {
+    let canonicalize = |dir: &Path| -> Result<PathBuf, String> {
+        dir.canonicalize()
+            .map_err(|e| format!("Cannot watch {}: {}", dir.display(), e))
+    };
+    // Input directories that don't exist have nothing to watch
+    let input_dirs: Vec<PathBuf> = inputs
+        .dirs()
+        .iter()
+        .filter(|dir| dir.exists())
+        .map(|dir| canonicalize(dir))
+        .collect::<Result<_, _>>()?;
+    let src_dir = canonicalize(&crate_dir.join("src"))?;
+    // The file may be gone once it has been rebuilt
+    let exe = env::current_exe().map_err(|e| format!("Cannot find own binary: {}", e))?;
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(sender).map_err(|e| format!("Cannot watch files: {}", e))?;
+    for dir in input_dirs.iter().chain([&src_dir]) {
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Cannot watch {}: {}", dir.display(), e))?;
+    }
+
+    let mut previous = env::var(PREVIOUS_VAR)
+        .ok()
+        .and_then(|text| decode_results(&text))
+        .unwrap_or_default();
+    let variants = inputs.available_variants(day).into_iter().collect();
+    run_and_report(
+        solution,
+        inputs,
+        answers_path,
+        day,
+        &variants,
+        &mut previous,
+    );
+    println!("Watching day {} for changes", day);
+
+    loop {
+        let mut events = vec![receiver.recv().map_err(|_| "File watcher stopped")?];
+        thread::sleep(DEBOUNCE);
+        events.extend(receiver.try_iter());
+
+        let mut changed_inputs = BTreeSet::new();
+        let mut module_changed = false;
+        for event in events {
+            let event = event.map_err(|e| format!("Cannot watch files: {}", e))?;
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                continue;
+            }
+            for path in &event.paths {
+                match classify(path, &input_dirs, &src_dir, day) {
+                    Some(Change::Input(variant)) => {
+                        changed_inputs.insert(variant);
+                    }
+                    Some(Change::Module) => module_changed = true,
+                    None => {}
+                }
+            }
+        }
+
+        if module_changed {
+            println!("\nday{}.rs changed, rebuilding", day);
+            eprintln!("{}", rebuild_and_restart(crate_dir, &exe, &previous));
+        } else if !changed_inputs.is_empty() {
+            let names: Vec<_> = changed_inputs.iter().map(|v| v.file_name(day)).collect();
+            println!("\n{} changed", names.join(", "));
+            run_and_report(
+                solution,
+                inputs,
+                answers_path,
+                day,
+                &changed_inputs,
+                &mut previous,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_changes() {
+        let input_dirs = [PathBuf::from("/in/2023/alice"), PathBuf::from("/in/2023")];
+        let src_dir = Path::new("/aoc/src");
+        let classify = |path: &str| classify(Path::new(path), &input_dirs, src_dir, 7);
+
+        assert_eq!(
+            classify("/in/2023/inp7_1.txt"),
+            Some(Change::Input(InputVariant::Example))
+        );
+        assert_eq!(
+            classify("/in/2023/alice/inp7_2.txt"),
+            Some(Change::Input(InputVariant::Real))
+        );
+        assert_eq!(
+            classify("/in/2023/inp7_3.txt"),
+            Some(Change::Input(InputVariant::Extra(3)))
+        );
+        assert_eq!(classify("/aoc/src/day7.rs"), Some(Change::Module));
+        assert_eq!(classify("/in/2023/inp17_1.txt"), None);
+        assert_eq!(classify("/in/2023/inp7_1.txt.swp"), None);
+        assert_eq!(classify("/in/2023/bob/inp7_2.txt"), None);
+        assert_eq!(classify("/aoc/src/day17.rs"), None);
+        assert_eq!(classify("/aoc/src/inp7_1.txt"), None);
+        assert_eq!(classify("/in/2023/day7.rs"), None);
+    }
+
+    #[test]
+    fn describes_differences() {
+        let answer = |a: &str| Ok(a.to_string());
+        let expected = ExpectedAnswer::Int(42);
+
+        assert_eq!(describe(&answer("42"), None, None), "42");
+        assert_eq!(
+            describe(&answer("42"), Some(&answer("42")), Some(&expected)),
+            "42 (unchanged), pass"
+        );
+        assert_eq!(
+            describe(&answer("41"), Some(&answer("42")), Some(&expected)),
+            "41 (was 42), FAIL: expected 42"
+        );
+        assert_eq!(
+            describe(
+                &answer("ABC"),
+                None,
+                Some(&ExpectedAnswer::Str("ABC".into()))
+            ),
+            "ABC, pass"
+        );
+        assert_eq!(
+            describe(
+                &Err("panicked: oops".to_string()),
+                Some(&answer("42")),
+                None
+            ),
+            "ERROR: panicked: oops (was 42)"
+        );
+    }
+
+    #[test]
+    fn results_survive_restart() {
+        let results = Results::from([
+            ((InputVariant::Example, 1), Ok("42".to_string())),
+            (
+                (InputVariant::Extra(3), 2),
+                Err("panicked: oops".to_string()),
+            ),
+        ]);
+        assert_eq!(decode_results(&encode_results(&results)), Some(results));
+    }
+}
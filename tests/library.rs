@@ -0,0 +1,46 @@
+//! The solvers and algorithms used as a library, the way other projects depend on them
+
+use aoc2023::day1::Day1;
+use aoc2023::day25::minimum_cut;
+use aoc2023::solution::{self, Answer, DynSolution, Solution};
+use aoc2023::{SOLUTIONS, YEAR, YEARS};
+use petgraph::graph::UnGraph;
+
+fn lines(text: &str) -> Vec<String> {
+    text.lines().map(|l| l.to_string()).collect()
+}
+
+#[test]
+fn solver_of_a_day() {
+    let input = Day1::parse(&lines("1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet")).unwrap();
+    assert_eq!(Day1::part1(&input), Answer::Int(142));
+}
+
+#[test]
+fn solvers_of_all_days() {
+    let days: Vec<u8> = SOLUTIONS.iter().map(|&(day, _)| day).collect();
+    assert_eq!(days, (1..=25).collect::<Vec<_>>());
+    assert_eq!(YEARS[0].0, YEAR);
+
+    let solution: &dyn DynSolution = solution::find(SOLUTIONS, 1).unwrap();
+    let input = solution
+        .parse_any(&lines("two1nine\nabcone2threexyz"))
+        .unwrap();
+    assert_eq!(solution.part2_any(&*input), Answer::Int(29 + 13));
+}
+
+#[test]
+fn minimum_cut_between_triangles() {
+    let mut graph = UnGraph::new_undirected();
+    let nodes: Vec<_> = ["a", "b", "c", "x", "y", "z"]
+        .into_iter()
+        .map(|name| graph.add_node(name.to_string()))
+        .collect();
+    for (from, to) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+        graph.add_edge(nodes[from], nodes[to], 1);
+    }
+
+    let (side, weight) = minimum_cut(&mut graph);
+    assert_eq!(weight, 1);
+    assert_eq!(side.split(',').count(), 3);
+}
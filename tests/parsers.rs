@@ -0,0 +1,102 @@
+//! The parsers of all days on broken input: they have to return an error, never panic
+//!
+//! `fuzz/` feeds them arbitrary bytes for longer, this only runs a quick round of mutated
+//! examples and random lines with every test run.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+use aoc2023::solution;
+use aoc2023::util::file_lines;
+use aoc2023::SOLUTIONS;
+use proptest::prelude::*;
+
+/// Change to a line of an input, the indices are taken modulo the lines and characters present
+#[derive(Debug, Clone)]
+enum Mutation {
+    Replace(usize, usize, char),
+    Insert(usize, usize, char),
+    Delete(usize, usize),
+    DropLine(usize),
+    /// Insert digits, to overflow the numbers of the input
+    Digits(usize, usize),
+}
+
+fn mutation() -> impl Strategy<Value = Mutation> {
+    // Mostly characters that appear in puzzle inputs, to get past the first checks
+    let c = || {
+        prop_oneof![
+            3 => prop::sample::select("0123456789abcxyz -,:;=<>{}@~#.%&|".chars().collect::<Vec<_>>()),
+            1 => any::<char>(),
+        ]
+    };
+    let index = any::<usize>;
+    prop_oneof![
+        (index(), index(), c()).prop_map(|(l, i, c)| Mutation::Replace(l, i, c)),
+        (index(), index(), c()).prop_map(|(l, i, c)| Mutation::Insert(l, i, c)),
+        (index(), index()).prop_map(|(l, i)| Mutation::Delete(l, i)),
+        index().prop_map(Mutation::DropLine),
+        (index(), index()).prop_map(|(l, i)| Mutation::Digits(l, i)),
+    ]
+}
+
+fn mutate(lines: &mut Vec<String>, mutation: &Mutation) {
+    if lines.is_empty() {
+        return;
+    }
+    let line_count = lines.len();
+    let edit = |lines: &mut Vec<String>, l: usize, f: &dyn Fn(&mut Vec<char>)| {
+        let line = &mut lines[l % line_count];
+        let mut chars = line.chars().collect();
+        f(&mut chars);
+        *line = chars.into_iter().collect();
+    };
+    match *mutation {
+        Mutation::Replace(l, i, c) => edit(lines, l, &|chars| {
+            if !chars.is_empty() {
+                let i = i % chars.len();
+                chars[i] = c;
+            }
+        }),
+        Mutation::Insert(l, i, c) => {
+            edit(lines, l, &|chars| chars.insert(i % (chars.len() + 1), c))
+        }
+        Mutation::Delete(l, i) => edit(lines, l, &|chars| {
+            if !chars.is_empty() {
+                chars.remove(i % chars.len());
+            }
+        }),
+        Mutation::DropLine(l) => {
+            lines.remove(l % line_count);
+        }
+        Mutation::Digits(l, i) => edit(lines, l, &|chars| {
+            let i = i % (chars.len() + 1);
+            chars.splice(i..i, "99999999999999999999".chars());
+        }),
+    }
+}
+
+/// Whether parsing `lines` with the solver of `day` returns, with or without an error
+fn parse_returns(day: usize, lines: &[String]) -> bool {
+    let solution = solution::find(SOLUTIONS, day as u8).unwrap();
+    panic::catch_unwind(AssertUnwindSafe(|| solution.parse_any(lines).is_ok())).is_ok()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+
+    #[test]
+    fn mutated_examples(day in 1..=SOLUTIONS.len(), mutations in prop::collection::vec(mutation(), 1..4)) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("inp{}_1.txt", day));
+        let mut lines = file_lines(&path).unwrap();
+        for mutation in &mutations {
+            mutate(&mut lines, mutation);
+        }
+        prop_assert!(parse_returns(day, &lines), "day {} panicked on {:?}", day, lines);
+    }
+
+    #[test]
+    fn random_lines(day in 1..=SOLUTIONS.len(), lines in prop::collection::vec(".{0,30}", 0..10)) {
+        prop_assert!(parse_returns(day, &lines), "day {} panicked on {:?}", day, lines);
+    }
+}